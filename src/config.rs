@@ -0,0 +1,456 @@
+//!
+//! Config file wrt colors, layout, keys and program defaults
+//! HanishKVC, 2022
+//!
+
+use std::fs;
+use std::path::Path;
+
+use sdl2::pixels::Color;
+use sdl2::keyboard::Keycode;
+
+use crate::entities::{self, objects};
+use crate::sdlx::{self, XRect};
+use crate::keys::KeyMap;
+
+
+/// The config file used, if present in the current dir and no --config is given
+pub const CONFIG_FILE_DEFAULT: &str = "gppgnd.cfg";
+
+/// The default time window (in records) over which the action markers fade out
+const MARKER_WINDOW_DEFAULT: usize = 300;
+
+/// The commandline args which override the [defaults] entries, as (arg, key)
+const ARGS_DEFAULTS: [(&str, &str); 7] = [
+    ("--mode", "mode"),
+    ("--src", "src"),
+    ("--save_interval", "save_interval"),
+    ("--fps", "fps"),
+    ("--virtball", "virtball"),
+    ("--interpolate", "interpolate"),
+    ("--trail", "trail"),
+];
+
+
+#[derive(Debug, Clone)]
+/// The tunables of the program, which can be set using a config file and or
+/// overridden from the commandline.
+///
+/// The config file is a simple ini like file, with [section] lines followed
+/// by key = value lines. Blank lines and lines starting with # are ignored.
+///
+/// * colors: r,g,b[,a] (each 0-255) or #rrggbb[aa]
+/// * positions: x,y in normalised 0.0-1.0 screen space
+/// * rects: x1,y1,x2,y2 in normalised 0.0-1.0 screen space
+/// * keys: sdl key names like A, F2, Space, Left
+/// * key sequences: [prefixkey] [Shift+]key, like S T or Shift+R
+///
+/// The values are got from the compile time defaults, to start with.
+pub struct Config {
+    /// [colors] background
+    pub color_bg: Color,
+    /// [colors] lteam
+    pub color_lteam: Color,
+    /// [colors] rteam
+    pub color_rteam: Color,
+    /// [colors] ball
+    pub color_ball: Color,
+    /// [sizes] player, as width,height in pixels
+    pub player_size: (u32, u32),
+    /// [sizes] ball, in pixels
+    pub ball_size: u32,
+    /// [font] file, if empty or not loadable, a system font is searched for
+    pub font_file: String,
+    /// [font] size
+    pub font_size: u16,
+    /// [layout] msg_score, msg_fps, msg_game, msg_unknown, msg_timed
+    pub msg_score_pos: (f32, f32),
+    pub msg_fps_pos: (f32, f32),
+    pub msg_game_pos: (f32, f32),
+    pub msg_unknown_pos: (f32, f32),
+    pub msg_timed_pos: (f32, f32),
+    /// [layout] timeline
+    pub timeline_rect: XRect,
+    /// [layout] eventlog
+    pub eventlog_rect: XRect,
+    /// [layout] eventlog_lines
+    pub eventlog_nlines: usize,
+    /// [keys] newkey = defaultkey, ie pressing newkey acts like defaultkey
+    pub keyremap: Vec<(Keycode, Keycode)>,
+    /// [bindings] command = keyseq [| keyseq ...]
+    pub keymap: KeyMap,
+    /// [markers] window, in records, over which the action markers fade out, 0 means the whole match
+    pub marker_window: usize,
+    /// [defaults] mode, src, save_interval, fps, virtball, interpolate, trail
+    pub mode: String,
+    pub src: String,
+    pub save_interval: usize,
+    pub fps: f32,
+    pub fvirtball: String,
+    pub interpolate: bool,
+    pub trail: f32,
+}
+
+impl Default for Config {
+
+    fn default() -> Self {
+        Config {
+            color_bg: entities::SCREEN_COLOR_BG,
+            color_lteam: Color::RED,
+            color_rteam: Color::BLUE,
+            color_ball: objects::BALL_COLOR,
+            player_size: (entities::ENTITY_WIDTH, entities::ENTITY_HEIGHT),
+            ball_size: objects::BALL_SIZE,
+            font_file: sdlx::TTF_FONT.to_string(),
+            font_size: sdlx::TTF_FONT_SIZE,
+            msg_score_pos: entities::MSG_SCORE_POS,
+            msg_fps_pos: entities::MSG_FPS_POS,
+            msg_game_pos: entities::MSG_GAME_POS,
+            msg_unknown_pos: entities::MSG_UNKNOWN_POS,
+            msg_timed_pos: entities::MSG_TIMED_POS,
+            timeline_rect: entities::TIMELINE_RECT,
+            eventlog_rect: entities::EVENTLOG_RECT,
+            eventlog_nlines: entities::EVENTLOG_NLINES,
+            keyremap: Vec::new(),
+            keymap: KeyMap::default(),
+            marker_window: MARKER_WINDOW_DEFAULT,
+            mode: String::from("random"),
+            src: String::new(),
+            save_interval: 0,
+            fps: entities::FRAMES_PER_SEC as f32,
+            fvirtball: String::new(),
+            interpolate: cfg!(feature = "inbetween_frames"),
+            trail: crate::TRAIL_SECONDS,
+        }
+    }
+
+}
+
+fn parse_num<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    return value.trim().parse::<T>().map_err(|_| format!("invalid number [{}]", value.trim()));
+}
+
+/// Parse a comma seperated list of exactly n numbers
+fn parse_nums<T: std::str::FromStr>(value: &str, n: usize) -> Result<Vec<T>, String> {
+    let parts: Vec<&str> = value.split(',').collect();
+    if parts.len() != n {
+        return Err(format!("expected {} comma seperated numbers, got [{}]", n, value.trim()));
+    }
+    let mut vals = Vec::new();
+    for part in parts {
+        vals.push(parse_num(part)?);
+    }
+    return Ok(vals);
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    return match value.trim() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(format!("expected true or false, got [{}]", value.trim())),
+    };
+}
+
+/// Parse r,g,b[,a] (each 0-255) or #rrggbb[aa]
+fn parse_color(value: &str) -> Result<Color, String> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        if (hex.len() != 6 && hex.len() != 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("expected #rrggbb or #rrggbbaa, got [{}]", value));
+        }
+        let c = |i: usize| u8::from_str_radix(&hex[i..i+2], 16).unwrap();
+        let a = if hex.len() == 8 { c(6) } else { 255 };
+        return Ok(Color::RGBA(c(0), c(2), c(4), a));
+    }
+    let n = value.split(',').count();
+    if n != 3 && n != 4 {
+        return Err(format!("expected r,g,b or r,g,b,a (each 0-255), got [{}]", value));
+    }
+    let c: Vec<u8> = parse_nums(value, n).map_err(|_| format!("expected r,g,b or r,g,b,a (each 0-255), got [{}]", value))?;
+    let a = if n == 4 { c[3] } else { 255 };
+    return Ok(Color::RGBA(c[0], c[1], c[2], a));
+}
+
+fn parse_pos(value: &str) -> Result<(f32, f32), String> {
+    let v: Vec<f32> = parse_nums(value, 2)?;
+    return Ok((v[0], v[1]));
+}
+
+fn parse_rect(value: &str) -> Result<XRect, String> {
+    let v: Vec<f32> = parse_nums(value, 4)?;
+    return Ok(((v[0], v[1]), (v[2], v[3])));
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let v: Vec<u32> = parse_nums(value, 2)?;
+    return Ok((v[0], v[1]));
+}
+
+fn parse_key(name: &str) -> Result<Keycode, String> {
+    return Keycode::from_name(name.trim()).ok_or(format!("unknown key name [{}]", name.trim()));
+}
+
+fn check_npos(name: &str, npos: (f32, f32)) -> Result<(), String> {
+    if (npos.0 < 0.0) || (npos.0 > 1.0) || (npos.1 < 0.0) || (npos.1 > 1.0) {
+        return Err(format!("[layout] {}: position {:?} should be within 0.0-1.0", name, npos));
+    }
+    return Ok(());
+}
+
+fn check_nrect(name: &str, nrect: XRect) -> Result<(), String> {
+    let ((x1, y1), (x2, y2)) = nrect;
+    check_npos(name, (x1, y1))?;
+    check_npos(name, (x2, y2))?;
+    if (x1 >= x2) || (y1 >= y2) {
+        return Err(format!("[layout] {}: rect {:?} should have x1 < x2 and y1 < y2", name, nrect));
+    }
+    return Ok(());
+}
+
+impl Config {
+
+    /// Set the given section.key to the given value, after parsing it.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) -> Result<(), String> {
+        match (section, key) {
+            ("colors", "background") => self.color_bg = parse_color(value)?,
+            ("colors", "lteam") => self.color_lteam = parse_color(value)?,
+            ("colors", "rteam") => self.color_rteam = parse_color(value)?,
+            ("colors", "ball") => self.color_ball = parse_color(value)?,
+            ("sizes", "player") => self.player_size = parse_size(value)?,
+            ("sizes", "ball") => self.ball_size = parse_num(value)?,
+            ("font", "file") => self.font_file = value.trim().to_string(),
+            ("font", "size") => self.font_size = parse_num(value)?,
+            ("layout", "msg_score") => self.msg_score_pos = parse_pos(value)?,
+            ("layout", "msg_fps") => self.msg_fps_pos = parse_pos(value)?,
+            ("layout", "msg_game") => self.msg_game_pos = parse_pos(value)?,
+            ("layout", "msg_unknown") => self.msg_unknown_pos = parse_pos(value)?,
+            ("layout", "msg_timed") => self.msg_timed_pos = parse_pos(value)?,
+            ("layout", "timeline") => self.timeline_rect = parse_rect(value)?,
+            ("layout", "eventlog") => self.eventlog_rect = parse_rect(value)?,
+            ("layout", "eventlog_lines") => self.eventlog_nlines = parse_num(value)?,
+            ("keys", _) => {
+                let newkey = parse_key(key)?;
+                let defkey = parse_key(value)?;
+                self.keyremap.retain(|km| km.0 != newkey);
+                self.keyremap.push((newkey, defkey));
+            },
+            ("bindings", _) => self.keymap.rebind(key, value)?,
+            ("markers", "window") => self.marker_window = parse_num(value)?,
+            ("defaults", "mode") => self.mode = value.trim().to_string(),
+            ("defaults", "src") => self.src = value.trim().to_string(),
+            ("defaults", "save_interval") => self.save_interval = parse_num(value)?,
+            ("defaults", "fps") => self.fps = parse_num(value)?,
+            ("defaults", "virtball") => self.fvirtball = value.trim().to_string(),
+            ("defaults", "interpolate") => self.interpolate = parse_bool(value)?,
+            ("defaults", "trail") => self.trail = parse_num(value)?,
+            ("colors", _) | ("sizes", _) | ("font", _) | ("layout", _) | ("markers", _) | ("defaults", _) => {
+                return Err(format!("unknown key [{}] {}", section, key));
+            },
+            _ => return Err(format!("unknown section [{}]", section)),
+        }
+        return Ok(());
+    }
+
+    /// Set a section.key=value, as got from the commandline
+    pub fn set_spec(&mut self, spec: &str) -> Result<(), String> {
+        let (skey, value) = spec.split_once('=').ok_or(format!("expected section.key=value, got [{}]", spec))?;
+        let (section, key) = skey.split_once('.').ok_or(format!("expected section.key=value, got [{}]", spec))?;
+        return self.set(section.trim(), key.trim(), value);
+    }
+
+    /// Load the given config file, on top of the current values.
+    /// Any error is reported along with the file and line number.
+    pub fn load_file(&mut self, fname: &str) -> Result<(), String> {
+        let data = fs::read_to_string(fname).map_err(|e| format!("{}: {}", fname, e))?;
+        let mut section = String::new();
+        for (i, line) in data.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                if !line.ends_with(']') {
+                    return Err(format!("{}:{}: unterminated section header [{}]", fname, i+1, line));
+                }
+                section = line[1..line.len()-1].trim().to_string();
+                continue;
+            }
+            if section.is_empty() {
+                return Err(format!("{}:{}: key before any [section]", fname, i+1));
+            }
+            let (key, value) = line.split_once('=').ok_or(format!("{}:{}: expected key = value, got [{}]", fname, i+1, line))?;
+            self.set(&section, key.trim(), value).map_err(|e| format!("{}:{}: {}", fname, i+1, e))?;
+        }
+        return Ok(());
+    }
+
+    /// Check that the values are usable together
+    pub fn validate(&self) -> Result<(), String> {
+        if (self.player_size.0 == 0) || (self.player_size.1 == 0) || (self.ball_size == 0) {
+            return Err("[sizes] player and ball sizes should be above 0".to_string());
+        }
+        if self.font_size == 0 {
+            return Err("[font] size should be above 0".to_string());
+        }
+        check_npos("msg_score", self.msg_score_pos)?;
+        check_npos("msg_fps", self.msg_fps_pos)?;
+        check_npos("msg_game", self.msg_game_pos)?;
+        check_npos("msg_unknown", self.msg_unknown_pos)?;
+        check_npos("msg_timed", self.msg_timed_pos)?;
+        check_nrect("timeline", self.timeline_rect)?;
+        check_nrect("eventlog", self.eventlog_rect)?;
+        if self.eventlog_nlines == 0 {
+            return Err("[layout] eventlog_lines should be above 0".to_string());
+        }
+        self.keymap.validate().map_err(|e| format!("[bindings] {}", e))?;
+        if !["random", "rclive", "rcg"].contains(&self.mode.as_str()) {
+            return Err(format!("[defaults] mode [{}] should be one of random, rclive or rcg", self.mode));
+        }
+        if !(self.fps > 0.0) {
+            return Err(format!("[defaults] fps [{}] should be above 0", self.fps));
+        }
+        if !(self.trail >= 0.0) {
+            return Err(format!("[defaults] trail [{}] should be 0 or above", self.trail));
+        }
+        return Ok(());
+    }
+
+    /// Map the pressed key to the default key it stands for, if any
+    pub fn map_key(&self, key: Keycode) -> Keycode {
+        for (newkey, defkey) in self.keyremap.iter() {
+            if *newkey == key {
+                return *defkey;
+            }
+        }
+        return key;
+    }
+
+    /// Build the config from the compile time defaults, the config file and
+    /// the overrides in the given commandline args, ie
+    ///
+    /// --config <path/file.cfg> # config file, else gppgnd.cfg if present
+    ///
+    /// --set <section.key=value> # override a config file entry, can be repeated
+    ///
+    /// --mode random
+    /// --mode rclive [--src <the network addr>]
+    /// --mode rcg --src <path/file>
+    ///
+    /// --save_interval <0 or above> # 0 disable saving playback screen
+    ///
+    /// --fps <playback fps>
+    ///
+    /// --interpolate <true|false> # interpolated movements mode or not
+    ///
+    /// --virtball <path/virtball.csv>
+    ///
+    /// --trail <seconds> # length of the trails behind ball and players, 0 disables
+    ///
+    /// The args other than --config override the corresponding [defaults] entry,
+    /// and are applied in the order given, after loading the config file. All the
+    /// values go through the same parsing and validation, as the config file ones.
+    pub fn from_args(args: &[String]) -> Result<Config, String> {
+        return Self::from_args_with(args, Some(CONFIG_FILE_DEFAULT));
+    }
+
+    /// Same as from_args, except that the config file loaded (if present), when
+    /// --config isnt given, is the specified default_cfg, if any.
+    pub fn from_args_with(args: &[String], default_cfg: Option<&str>) -> Result<Config, String> {
+        let mut config = Config::default();
+        let mut fconfig = None;
+        let mut sets = Vec::new();
+        let mut i = 0;
+        while i < args.len() {
+            let arg = args[i].as_str();
+            let defkey = ARGS_DEFAULTS.iter().find(|ad| ad.0 == arg).map(|ad| ad.1);
+            if (arg != "--config") && (arg != "--set") && defkey.is_none() {
+                return Err(format!("unknown arg [{}]", arg));
+            }
+            if i+1 >= args.len() {
+                return Err(format!("{} needs a value", arg));
+            }
+            let value = args[i+1].clone();
+            if arg == "--config" {
+                fconfig = Some(value);
+            } else if arg == "--set" {
+                sets.push((arg, value.clone(), value));
+            } else {
+                sets.push((arg, value.clone(), format!("defaults.{}={}", defkey.unwrap(), value)));
+            }
+            i += 2;
+        }
+        if let Some(default_cfg) = default_cfg {
+            if fconfig.is_none() && Path::new(default_cfg).exists() {
+                fconfig = Some(default_cfg.to_string());
+            }
+        }
+        if let Some(fconfig) = fconfig {
+            config.load_file(&fconfig)?;
+            eprintln!("INFO:GPPGND:Config:Loaded:{}", fconfig);
+        }
+        for (arg, value, spec) in sets {
+            config.set_spec(&spec).map_err(|e| format!("{} {}: {}", arg, value, e))?;
+        }
+        config.validate()?;
+        return Ok(config);
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_color_formats() {
+        assert_eq!(parse_color("200,0,0"), Ok(Color::RGBA(200, 0, 0, 255)));
+        assert_eq!(parse_color(" 0, 0, 200, 128 "), Ok(Color::RGBA(0, 0, 200, 128)));
+        assert_eq!(parse_color("#c80000"), Ok(Color::RGBA(200, 0, 0, 255)));
+        assert_eq!(parse_color("#0000C880"), Ok(Color::RGBA(0, 0, 200, 128)));
+        assert!(parse_color("#c800").is_err());
+        assert!(parse_color("#c8000g").is_err());
+        assert!(parse_color("200,0").is_err());
+        assert!(parse_color("256,0,0").is_err());
+        assert!(parse_color("red").is_err());
+    }
+
+    fn args(vargs: &[&str]) -> Vec<String> {
+        return vargs.iter().map(|a| a.to_string()).collect();
+    }
+
+    #[test]
+    fn from_args_overrides_in_order() {
+        let config = Config::from_args_with(&args(&["--set", "defaults.fps=15", "--fps", "20", "--mode", "rcg", "--src", "a.rcg", "--set", "colors.lteam=#ff8000", "--interpolate", "true"]), None).unwrap();
+        assert_eq!(config.fps, 20.0);
+        assert_eq!((config.mode.as_str(), config.src.as_str()), ("rcg", "a.rcg"));
+        assert_eq!(config.color_lteam, Color::RGBA(255, 128, 0, 255));
+        assert!(config.interpolate);
+        let config = Config::from_args_with(&args(&["--trail", "2", "--set", "defaults.trail=0"]), None).unwrap();
+        assert_eq!(config.trail, 0.0);
+    }
+
+    #[test]
+    fn from_args_errors() {
+        assert!(Config::from_args_with(&args(&["--fps", "abc"]), None).unwrap_err().starts_with("--fps abc:"));
+        assert!(Config::from_args_with(&args(&["--fps", "0"]), None).unwrap_err().contains("fps"));
+        assert!(Config::from_args_with(&args(&["--mode", "live"]), None).unwrap_err().contains("mode"));
+        assert!(Config::from_args_with(&args(&["--interpolate", "maybe"]), None).is_err());
+        assert!(Config::from_args_with(&args(&["--bogus", "1"]), None).is_err());
+        assert!(Config::from_args_with(&args(&["--src"]), None).is_err());
+    }
+
+    #[test]
+    fn from_args_default_cfg() {
+        let fcfg = std::env::temp_dir().join("gppgnd-test-default.cfg").to_string_lossy().to_string();
+        std::fs::write(&fcfg, "[defaults]\nfps = 12\nmode = rcg\n").unwrap();
+        let config = Config::from_args_with(&args(&["--fps", "20"]), Some(&fcfg)).unwrap();
+        assert_eq!((config.fps, config.mode.as_str()), (20.0, "rcg"));
+        let config = Config::from_args_with(&args(&["--config", "/nonexistent/gppgnd.cfg"]), Some(&fcfg));
+        assert!(config.is_err());
+        std::fs::remove_file(&fcfg).unwrap();
+        let config = Config::from_args_with(&args(&[]), Some(&fcfg)).unwrap();
+        assert_eq!(config.mode, "random");
+    }
+
+}
@@ -0,0 +1,115 @@
+//!
+//! A scrolling event log panel
+//! HanishKVC, 2022
+//!
+
+use sdl2::pixels::Color;
+use sdl2::render::BlendMode;
+
+use crate::sdlx::{SdlX, XRect};
+
+const EVENTLOG_COLOR_BG: Color = Color::RGBA(40, 40, 40, 160);
+const EVENTLOG_COLOR_HEAD: Color = Color::YELLOW;
+const EVENTLOG_COLOR_TEXT: Color = Color::WHITE;
+const EVENTLOG_COLOR_SCROLLED: Color = Color::CYAN;
+
+
+#[derive(Debug)]
+/// Show a panel on the screen, with the latest few timestamped game events,
+/// which can be scrolled back through.
+///
+/// It also helps map a position on the panel back to the time of the event
+/// shown there, to allow seeking.
+pub struct EventLog {
+    /// Whether the panel is shown or not
+    pub bshow: bool,
+    /// The panel on the screen, in normalised 0.0-1.0 space.
+    nrect: XRect,
+    /// The number of entries shown at a time
+    nlines: usize,
+    /// The entries shown currently, as (time, text), ordered by time
+    entries: Vec<(usize, String)>,
+    /// The number of latest entries skipped, ie how far back one has scrolled
+    scroll: usize,
+}
+
+impl EventLog {
+
+    pub fn new(nrect: XRect, nlines: usize) -> EventLog {
+        EventLog {
+            bshow: false,
+            nrect: nrect,
+            nlines: nlines,
+            entries: Vec::new(),
+            scroll: 0,
+        }
+    }
+
+    /// The number of entries shown at a time
+    pub fn nlines(&self) -> usize {
+        return self.nlines;
+    }
+
+    /// The number of latest entries to skip, when getting the entries to show
+    pub fn scroll(&self) -> usize {
+        return self.scroll;
+    }
+
+    /// Scroll back (+ve) or forward (-ve) by the given number of entries.
+    /// The log follows the latest entries, when not scrolled back.
+    pub fn scroll_by(&mut self, delta: isize) {
+        self.scroll = (self.scroll as isize + delta).max(0) as usize;
+    }
+
+    /// Set the entries to show, as got wrt the current scroll position.
+    /// If scrolled back beyond the oldest entry, the scroll is pulled back.
+    pub fn set_entries(&mut self, entries: Vec<(usize, String)>) {
+        if (entries.len() < self.nlines) && (self.scroll > 0) {
+            self.scroll = self.scroll.saturating_sub(self.nlines - entries.len());
+        }
+        self.entries = entries;
+    }
+
+    /// The height of each line, in normal space. The heading takes up 2 lines.
+    fn nlh(&self) -> f32 {
+        let ((_nx1,ny1),(_nx2,ny2)) = self.nrect;
+        return (ny2-ny1)/(self.nlines + 2) as f32;
+    }
+
+    /// Check if the given position (in normal space) is on the panel
+    pub fn contains(&self, nx: f32, ny: f32) -> bool {
+        let ((nx1,ny1),(nx2,ny2)) = self.nrect;
+        return self.bshow && (nx >= nx1) && (nx <= nx2) && (ny >= ny1) && (ny <= ny2);
+    }
+
+    /// Map y position on the panel (in normal space) to the time of the entry
+    /// shown there, if any.
+    pub fn ny2time(&self, ny: f32) -> Option<usize> {
+        let ((_nx1,ny1),(_nx2,_ny2)) = self.nrect;
+        let line = ((ny - ny1)/self.nlh()).floor() as isize - 2;
+        if (line < 0) || (line as usize >= self.entries.len()) {
+            return None;
+        }
+        return Some(self.entries[line as usize].0);
+    }
+
+    /// Draw the panel, with the heading indicating if scrolled back.
+    pub fn draw(&self, sx: &mut SdlX) {
+        if !self.bshow {
+            return;
+        }
+        let ((nx1,ny1),(nx2,ny2)) = self.nrect;
+        let nlh = self.nlh();
+        sx.wc.set_blend_mode(BlendMode::Blend);
+        sx.wc.set_draw_color(EVENTLOG_COLOR_BG);
+        sx.nn_fill_rect(nx1, ny1, nx2-nx1, ny2-ny1);
+        let shead = if self.scroll > 0 { format!("Events [-{}]", self.scroll) } else { "Events".to_string() };
+        let hcolor = if self.scroll > 0 { EVENTLOG_COLOR_SCROLLED } else { EVENTLOG_COLOR_HEAD };
+        sx.n_string(nx1+0.01, ny1+nlh*0.6, &shead, hcolor);
+        for (i, (time, text)) in self.entries.iter().enumerate() {
+            let ny = ny1 + nlh*(i as f32 + 2.2);
+            sx.n_string(nx1+0.01, ny, &format!("{:5} {}", time, text), EVENTLOG_COLOR_TEXT);
+        }
+    }
+
+}
@@ -3,6 +3,8 @@
 //! HanishKVC, 2022
 //!
 
+use std::collections::VecDeque;
+
 use sdl2::{pixels::Color, rect::Rect};
 use sdl2::ttf::Font;
 use sdl2::surface::Surface;
@@ -10,6 +12,7 @@ use sdl2::gfx::primitives::DrawRenderer;
 use sdl2::render::BlendMode;
 
 use crate::sdlx::{self, SdlX};
+use crate::entities::interpolate::{self, Interpolator};
 
 use sdlx::COLOR_INVISIBLE;
 
@@ -23,7 +26,7 @@ use sdlx::COLOR_INVISIBLE;
 pub struct GEntity<'a> {
     /// A textual id of the entity, the same is cached in a image form
     /// in the ids member.
-    _id: String,
+    id: String,
     /// Position of the entity in normal 0.0-1.0 space
     npos: (f32, f32),
     /// width, height in screen space dimensions
@@ -40,11 +43,29 @@ pub struct GEntity<'a> {
     pub colorsel: u8,
     /// Should the entity be moved back into screen, if it goes out
     onscreen: bool,
-    /// A cache of the Id string, as a SDL surface
-    ids: Surface<'a>,
-    /// Any motion vector that should be used to move entity,
-    /// when next frame is called.
-    mov: (f32, f32),
+    /// A cache of the Id string, as a SDL surface.
+    /// If there is no ttf font, the id is drawn using the builtin bitmap font.
+    ids: Option<Surface<'a>>,
+    /// The interpolator used wrt interpolated movements
+    interpolator: Interpolator,
+    /// The (start, end) positions wrt the interpolated movement
+    /// setup using move_to_in_frames.
+    movseg: ((f32, f32), (f32, f32)),
+    /// The start position wrt the previous interpolated movement
+    movprev: (f32, f32),
+    /// The position to be moved to after the current interpolated movement, if known
+    movnext: Option<(f32, f32)>,
+    /// The velocity (per record) at the start of the interpolated movement, if known
+    movvel: Option<(f32, f32)>,
+    /// The total number of frames (could be fractional), wrt the interpolated movement
+    movframes_total: f32,
+    /// The number of frames (could be fractional) remaining, wrt
+    /// the interpolated movement setup using move_to_in_frames.
+    movframes: f32,
+    /// The velocity (per record) wrt the position set/moved to last, if known
+    vel: Option<(f32, f32)>,
+    /// The factor by which velocity decays per record
+    veldecay: f32,
     /// Internal member - half width
     hw: i32,
     nhw: f32,
@@ -74,6 +95,21 @@ pub struct GEntity<'a> {
     /// Color of the right outer line
     /// Dont show line if invisible color
     rl_color: Color,
+    /// Body direction in degrees (clockwise from +ve x-axis), if known
+    body_angle: Option<f32>,
+    /// Neck direction in degrees relative to the body direction, if known
+    neck_angle: Option<f32>,
+    /// View width in degrees, wrt the view cone
+    view_width: f32,
+    /// Color of the body direction tick
+    /// Dont show if invisible color
+    body_color: Color,
+    /// Color of the neck direction line
+    /// Dont show if invisible color
+    neck_color: Color,
+    /// Color of the view cone, ideally translucent
+    /// Dont show if invisible color
+    view_color: Color,
     /// Vector of extra graphics
     gextras: Vec<GEDrawPrimitive>,
 
@@ -82,10 +118,10 @@ pub struct GEntity<'a> {
 impl<'a> GEntity<'a> {
 
     /// Create a new instance of the Graphical Entity
-    pub fn new(id: &str, npos: (f32, f32), width_height: (u32, u32), color: Color, font: &'a Font) -> GEntity<'a> {
-        let ts = sdlx::text_surface(font, id, Color::WHITE);
+    pub fn new(id: &str, npos: (f32, f32), width_height: (u32, u32), color: Color, font: Option<&'a Font>) -> GEntity<'a> {
+        let ts = font.map(|font| sdlx::text_surface(font, id, Color::WHITE));
         GEntity {
-            _id: id.to_string(),
+            id: id.to_string(),
             npos: npos,
             width_height,
             radius: ((width_height.0 + width_height.1)/2) as i16,
@@ -94,7 +130,15 @@ impl<'a> GEntity<'a> {
             colorsel: 0x01,
             onscreen: true,
             ids: ts,
-            mov: (0.0, 0.0),
+            interpolator: Interpolator::Linear,
+            movseg: (npos, npos),
+            movprev: npos,
+            movnext: None,
+            movvel: None,
+            movframes_total: 0.0,
+            movframes: 0.0,
+            vel: None,
+            veldecay: 1.0,
             hw: (width_height.0/2) as i32,
             hh: (width_height.1/2) as i32,
             nw: 0.05,
@@ -110,6 +154,12 @@ impl<'a> GEntity<'a> {
             bl_color: COLOR_INVISIBLE,
             ll_color: COLOR_INVISIBLE,
             rl_color: COLOR_INVISIBLE,
+            body_angle: None,
+            neck_angle: None,
+            view_width: 90.0,
+            body_color: COLOR_INVISIBLE,
+            neck_color: COLOR_INVISIBLE,
+            view_color: COLOR_INVISIBLE,
             gextras: Vec::new(),
         }
     }
@@ -140,19 +190,40 @@ impl<'a> GEntity<'a> {
         }
     }
 
+    /// The current position of the gentity in normal 0.0-1.0 space
+    pub fn pos(&self) -> (f32, f32) {
+        return self.npos;
+    }
+
+    /// The position set/moved to last, in normal space. In the interpolated movements
+    /// mode, the gentity could still be moving towards it.
+    pub fn pos_latest(&self) -> (f32, f32) {
+        return self.movseg.1;
+    }
+
+    /// The velocity (per record) wrt the position set/moved to last, if known
+    pub fn vel(&self) -> Option<(f32, f32)> {
+        return self.vel;
+    }
+
     /// Convert the gentity's position into screen space from normal space
-    pub fn ipos(&self) -> (i32, i32) {
-        let (prgw, prgh) = sdlx::get_prg_resolution();
-        ((self.npos.0 * prgw as f32).round() as i32, (self.npos.1 * prgh as f32).round() as i32)
+    pub fn ipos(&self, sx: &SdlX) -> (i32, i32) {
+        let (fx, fy) = sx.n2s.d2o(self.npos);
+        (fx.round() as i32, fy.round() as i32)
     }
 
     /// Set absolute position of the gentity in normal 0.0-1.0 space
+    ///
+    /// NOTE: Any pending interpolated movement is cleared.
     pub fn pos_set_abs(&mut self, fx: f32, fy: f32) {
         self.npos = (fx, fy);
+        self.movframes = 0.0;
         self.npos_fix();
+        self.movseg = (self.npos, self.npos);
     }
 
     /// Set relative position of the gentity in normal 0.0-1.0 space
+    #[allow(dead_code)]
     pub fn pos_set_rel(&mut self, fx: f32, fy: f32) {
         self.npos = (self.npos.0 + fx, self.npos.1 + fy);
         self.npos_fix();
@@ -163,22 +234,91 @@ impl<'a> GEntity<'a> {
     /// next_frame will be called, as required.
     ///
     /// NOTE: THis is for use in the interpolated movements mode.
+    ///
+    /// The path taken depends on the interpolator set. The velocity (if any) set
+    /// wrt the current position, is used only if the previous movement was completed.
     pub fn move_to_in_frames(&mut self, fpos: (f32, f32), frames: f32) {
-        let dx = (fpos.0 - self.npos.0)/frames;
-        let dy = (fpos.1 - self.npos.1)/frames;
-        self.mov = (dx, dy);
+        if self.movframes > 0.0 {
+            self.movvel = None;
+        } else {
+            self.movvel = self.vel;
+        }
+        self.movprev = self.movseg.0;
+        self.movseg = (self.npos, fpos);
+        self.movnext = None;
+        self.movframes_total = frames.max(1.0);
+        self.movframes = self.movframes_total;
+    }
+
+    /// Set the position to be moved to after the current interpolated movement, if known.
+    /// Interpolators like Catmull-Rom need it, refer move_pos.
+    pub fn set_move_next(&mut self, npos: Option<(f32, f32)>) {
+        self.movnext = npos;
+    }
+
+    /// Set the velocity (per record) wrt the position set/moved to last, if known,
+    /// along with the factor by which it decays per record.
+    pub fn set_vel(&mut self, vel: Option<(f32, f32)>, veldecay: f32) {
+        self.vel = vel;
+        self.veldecay = veldecay;
+    }
+
+    /// Set the interpolator to use wrt interpolated movements
+    pub fn set_interpolator(&mut self, interpolator: Interpolator) {
+        self.interpolator = interpolator;
+    }
+
+    /// The position at t (0.0-1.0) along the current interpolated movement
+    ///
+    /// NOTE: Catmull-Rom falls back to linear, if the position after the current
+    /// movement is not known (like wrt live or random sources, reverse playback).
+    fn move_pos(&self, t: f32) -> (f32, f32) {
+        let (p0, p1) = self.movseg;
+        match self.interpolator {
+            Interpolator::Linear => interpolate::linear(p0, p1, t),
+            Interpolator::CatmullRom => {
+                if let Some(p2) = self.movnext {
+                    interpolate::catmull_rom(self.movprev, p0, p1, p2, t)
+                } else {
+                    interpolate::linear(p0, p1, t)
+                }
+            },
+            Interpolator::Physics => {
+                if let Some(v0) = self.movvel {
+                    interpolate::decay_with_velocity(p0, p1, v0, self.veldecay, t)
+                } else {
+                    interpolate::linear(p0, p1, t)
+                }
+            },
+        }
     }
 
     /// Update the position of the gentity, wrt interpolated movement.
-    /// It uses the move vector setup using move_to_in_frames call,
-    /// to update the position.
+    /// It uses the movement setup using move_to_in_frames call,
+    /// to update the position, till the specified position is reached.
     pub fn next_frame(&mut self) {
-        self.pos_set_rel(self.mov.0, self.mov.1);
+        if self.movframes <= 0.0 {
+            return;
+        }
+        self.movframes -= self.movframes.min(1.0);
+        let t = 1.0 - self.movframes/self.movframes_total;
+        self.npos = self.move_pos(t);
+        self.npos_fix();
+    }
+
+    /// Complete any pending interpolated movement immidiately, so that
+    /// the gentity is at the position specified using move_to_in_frames.
+    pub fn move_finish(&mut self) {
+        if self.movframes > 0.0 {
+            self.npos = self.movseg.1;
+            self.npos_fix();
+        }
+        self.movframes = 0.0;
     }
 
     fn update_base_graphicelements(&mut self, sx: &mut SdlX) {
-        self.nw = sx.n2s.o2dx(self.width_height.0 as f32);
-        self.nh = sx.n2s.o2dy(self.width_height.1 as f32);
+        self.nw = sx.n2s.o2dx_len(self.width_height.0 as f32);
+        self.nh = sx.n2s.o2dy_len(self.width_height.1 as f32);
         self.nhw = self.nw/2.0;
         self.nhh = self.nh/2.0;
         self.nhlw = self.nh*0.2;
@@ -244,9 +384,13 @@ impl<'a> GEntity<'a> {
     /// * the fill color (which can be partly modified using fcolor)
     /// * a arc (wrt/including its radius, angle and color)
     /// * a set of outer lines and their colors
+    /// * the body direction, neck direction and view cone
     /// * gextras (GEDrawPrimitive based graphics)
     pub fn draw(&mut self, sx: &mut SdlX) {
         self.update_base_graphicelements(sx);
+        sx.wc.set_blend_mode(BlendMode::Blend);
+        self.draw_trails(sx);
+        self.draw_viewcone(sx);
         let color;
         if self.fcolor < 0.0 {
             color = self.color;
@@ -255,21 +399,28 @@ impl<'a> GEntity<'a> {
         }
         sx.wc.set_draw_color(color);
         sx.wc.set_blend_mode(BlendMode::Blend);
-        let ipos = self.ipos();
+        let ipos = self.ipos(sx);
         if cfg!(feature="gentity_circle") {
             sx.wc.filled_circle(ipos.0 as i16, ipos.1 as i16, self.radius, self.color).unwrap();
         } else {
             sx.ns_fill_rect_mid(self.npos.0, self.npos.1, self.width_height.0, self.width_height.1);
         }
-        let tx = self.ids.as_texture(&sx.wctc).unwrap();
-        sx.wc.copy(&tx, None, Some(Rect::new(ipos.0-self.hw, ipos.1-self.hh, self.width_height.0, self.width_height.1))).unwrap();
+        if let Some(ids) = &self.ids {
+            let tx = ids.as_texture(&sx.wctc).unwrap();
+            sx.wc.copy(&tx, None, Some(Rect::new(ipos.0-self.hw, ipos.1-self.hh, self.width_height.0, self.width_height.1))).unwrap();
+        } else {
+            let hcw = (sdlx::STRING_CHAR_PIXEL_WIDTH*0.5) as i32;
+            let hch = (sdlx::STRING_CHAR_PIXEL_HEIGHT*0.5) as i32;
+            let tx = ipos.0 - hcw*self.id.len() as i32;
+            sx.wc.string(tx as i16, (ipos.1-hch) as i16, &self.id, Color::WHITE).unwrap();
+        }
         if self.arc_nradius > 0.0 {
             let rad = (self.radius as f32 * self.arc_nradius).round() as i16;
             let edeg = (self.arc_nangle * 359.0).round() as i16;
             sx.ns_arc(self.npos.0, self.npos.1, rad, 0, edeg, 3, self.arc_color);
         }
-        drop(tx);
         self.draw_outerlines(sx);
+        self.draw_directions(sx);
         self.draw_gextras(sx);
     }
 
@@ -278,12 +429,12 @@ impl<'a> GEntity<'a> {
 impl std::fmt::Debug for GEntity<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("GEntity")
-            .field("_id", &self._id)
+            .field("id", &self.id)
             .field("pos", &self.npos)
             .field("whr", &self.width_height)
             .field("color", &self.color)
             .field("onscreen", &self.onscreen)
-            .field("move", &self.mov)
+            .field("move", &self.movseg)
             .finish()
     }
 }
@@ -344,6 +495,67 @@ impl<'a> GEntity<'a> {
         self.rl_color = color;
     }
 
+    /// Set the body direction (degrees, clockwise from +ve x-axis) and its tick color
+    pub fn set_body(&mut self, angle: f32, color: Color) {
+        self.body_angle = Some(angle);
+        self.body_color = color;
+    }
+
+    /// Set the neck direction (degrees, relative to body direction) and its line color
+    pub fn set_neck(&mut self, angle: f32, color: Color) {
+        self.neck_angle = Some(angle);
+        self.neck_color = color;
+    }
+
+    /// Set the view width (degrees) and color of the view cone
+    pub fn set_viewcone(&mut self, width: f32, color: Color) {
+        self.view_width = width;
+        self.view_color = color;
+    }
+
+    /// Set the colors wrt the body direction tick, neck direction line and view cone,
+    /// leaving the directions as is.
+    pub fn set_dir_colors(&mut self, body_color: Color, neck_color: Color, view_color: Color) {
+        self.body_color = body_color;
+        self.neck_color = neck_color;
+        self.view_color = view_color;
+    }
+
+    /// The absolute direction the gentity is looking at, if known
+    fn look_angle(&self) -> Option<f32> {
+        let body = self.body_angle?;
+        return Some(body + self.neck_angle.unwrap_or(0.0));
+    }
+
+    /// Draw the view cone, centered along the look direction,
+    /// provided its color is not invisible
+    fn draw_viewcone(&self, sx: &mut SdlX) {
+        if self.view_color == COLOR_INVISIBLE {
+            return;
+        }
+        if let Some(look) = self.look_angle() {
+            let hview = self.view_width/2.0;
+            let sa = (look - hview).round() as i16;
+            let ea = (look + hview).round() as i16;
+            sx.ns_pie(self.npos.0, self.npos.1, self.radius*3, sa, ea, self.view_color);
+        }
+    }
+
+    /// Draw the body direction tick and the neck direction line,
+    /// provided their colors are not invisible
+    fn draw_directions(&self, sx: &mut SdlX) {
+        if let Some(body) = self.body_angle {
+            if self.body_color != COLOR_INVISIBLE {
+                sx.ns_radial_line(self.npos.0, self.npos.1, self.radius/2, self.radius, body, 3, self.body_color);
+            }
+        }
+        if let Some(look) = self.look_angle() {
+            if self.neck_color != COLOR_INVISIBLE {
+                sx.ns_radial_line(self.npos.0, self.npos.1, 0, (self.radius*3)/2, look, 1, self.neck_color);
+            }
+        }
+    }
+
 }
 
 
@@ -368,6 +580,10 @@ pub enum GEDrawPrimitive {
     #[allow(dead_code)]
     /// RemainingFramesCnt, Line type (Top,Bottom,Left,Right), RelativePositionWrtCorrespondingBoundry, Color
     NLine{ remfc: isize, linetype: GELineType, radratio: f32, color: Color },
+    /// RemainingFramesCnt, (Time, Position)s (oldest first) the gentity was set/moved to,
+    /// MaxRecordsToRemember, Color (faded towards the oldest position).
+    /// It is drawn below the gentity.
+    NTrail{ remfc: isize, points: VecDeque<(usize, (f32, f32))>, maxpoints: usize, color: Color },
 }
 
 impl GEDrawPrimitive {
@@ -377,7 +593,8 @@ impl GEDrawPrimitive {
     fn life_decrement_need_removal(&mut self) -> bool {
         let mut bremove = false;
         match self {
-            GEDrawPrimitive::NSArc{ remfc, radratio: _, arcangles: _, color: _ } => {
+            GEDrawPrimitive::NSArc{ remfc, radratio: _, arcangles: _, color: _ } |
+            GEDrawPrimitive::NTrail{ remfc, points: _, maxpoints: _, color: _ } => {
                 if *remfc > 0 {
                     *remfc -= 1;
                     if *remfc == 0 {
@@ -405,6 +622,59 @@ impl<'a> GEntity<'a> {
         self.gextras.push(ge);
     }
 
+    /// Remember the position set/moved to wrt the given time, in any trails.
+    ///
+    /// Any positions wrt the same or later time are dropped first, so that the
+    /// trail remains in sync, when seeking back (reverse playback included).
+    /// Positions older than maxpoints records, relative to the given time, are
+    /// forgotten.
+    pub fn trail_add(&mut self, time: usize, npos: (f32, f32)) {
+        for ge in &mut self.gextras {
+            if let GEDrawPrimitive::NTrail { remfc: _, points, maxpoints, color: _ } = ge {
+                while points.back().map_or(false, |p| p.0 >= time) {
+                    points.pop_back();
+                }
+                points.push_back((time, npos));
+                while points.front().map_or(false, |p| p.0 + *maxpoints < time) {
+                    points.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Set up a trail, remembering the positions wrt upto the given number of records.
+    /// Any existing trail is removed. If maxpoints is 0, no trail is set up.
+    pub fn trail_set(&mut self, maxpoints: usize, color: Color) {
+        self.gextras.retain(|ge| !matches!(ge, GEDrawPrimitive::NTrail { .. }));
+        if maxpoints > 0 {
+            self.gextras_add(GEDrawPrimitive::NTrail { remfc: -1, points: VecDeque::new(), maxpoints, color });
+        }
+    }
+
+    /// Draw the trails, as a polyline from the current position through the
+    /// remembered positions, fading out towards the oldest position.
+    ///
+    /// The latest position is skipped, as in the interpolated movements mode,
+    /// it could still be getting moved to.
+    fn draw_trails(&self, sx: &mut SdlX) {
+        for ge in &self.gextras {
+            if let GEDrawPrimitive::NTrail { remfc: _, points, maxpoints, color } = ge {
+                let latest = match points.back() {
+                    Some(point) => point.0,
+                    None => continue,
+                };
+                let mut prev = self.npos;
+                for (time, point) in points.iter().rev().skip(1) {
+                    let age = (latest - time) as f32/(*maxpoints as f32);
+                    let alpha = (color.a as f32 * (1.0 - age).max(0.0)).round() as u8;
+                    let fcolor = Color::RGBA(color.r, color.g, color.b, alpha);
+                    sx.nn_thick_line(prev.0, prev.1, point.0, point.1, self.nhlw, fcolor);
+                    prev = *point;
+                }
+            }
+        }
+    }
+
     /// Draws starting from the end of the list (ie from the last/latest
     /// added entry towards the oldest).
     ///
@@ -422,6 +692,7 @@ impl<'a> GEntity<'a> {
                 GEDrawPrimitive::NLine { remfc: _, linetype, radratio, color } => {
                     self.draw_geline(sx, linetype.clone(), *radratio, *color);
                 },
+                GEDrawPrimitive::NTrail { .. } => (),
             }
             let ge = &mut self.gextras[i];
             if ge.life_decrement_need_removal() {
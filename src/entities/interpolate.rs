@@ -0,0 +1,124 @@
+//!
+//! Interpolation of positions between records
+//! HanishKVC, 2022
+//!
+
+use std::fmt::Display;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The interpolators one can choose from, wrt interpolated movements
+pub enum Interpolator {
+    /// Straight line, constant step movement.
+    Linear,
+    /// Catmull-Rom spline through the neighbouring positions.
+    CatmullRom,
+    /// Decaying velocity based movement, similar to the rcss simulation.
+    /// Falls back to linear, if velocity is not known.
+    Physics,
+}
+
+impl Display for Interpolator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let data = match self {
+            Interpolator::Linear => "Linear",
+            Interpolator::CatmullRom => "CatmullRom",
+            Interpolator::Physics => "Physics",
+        };
+        f.write_str(data)
+    }
+}
+
+impl Interpolator {
+
+    /// The interpolator to switch to, when cycling through them
+    pub fn next(&self) -> Interpolator {
+        match self {
+            Interpolator::Linear => Interpolator::CatmullRom,
+            Interpolator::CatmullRom => Interpolator::Physics,
+            Interpolator::Physics => Interpolator::Linear,
+        }
+    }
+
+}
+
+
+/// Position at t (0.0-1.0) along the straight line from p0 to p1
+pub fn linear(p0: (f32,f32), p1: (f32,f32), t: f32) -> (f32,f32) {
+    return (p0.0 + (p1.0-p0.0)*t, p0.1 + (p1.1-p0.1)*t);
+}
+
+/// Position at t (0.0-1.0) along the uniform Catmull-Rom spline segment
+/// from p0 to p1, with pm1 and p2 being the positions before and after it.
+pub fn catmull_rom(pm1: (f32,f32), p0: (f32,f32), p1: (f32,f32), p2: (f32,f32), t: f32) -> (f32,f32) {
+    let t2 = t*t;
+    let t3 = t2*t;
+    let cr = |am1: f32, a0: f32, a1: f32, a2: f32| -> f32 {
+        0.5 * ((2.0*a0) + (a1-am1)*t + (2.0*am1 - 5.0*a0 + 4.0*a1 - a2)*t2 + (3.0*a0 - am1 - 3.0*a1 + a2)*t3)
+    };
+    return (cr(pm1.0, p0.0, p1.0, p2.0), cr(pm1.1, p0.1, p1.1, p2.1));
+}
+
+/// The fraction of the distance covered till cycle x (can be fractional),
+/// by a object whose velocity decays by the given factor every cycle,
+/// relative to the distance covered in the 1st cycle.
+fn decay_distance(decay: f32, x: f32) -> f32 {
+    if (1.0-decay).abs() < 0.0001 {
+        return x;
+    }
+    return (1.0 - decay.powf(x))/(1.0 - decay);
+}
+
+/// Position at t (0.0-1.0) wrt a one cycle movement from p0 to p1, for a object
+/// starting with velocity v0 (per cycle) at p0, which decays by the given factor.
+///
+/// Any difference between where the velocity would take the object and p1
+/// (ie due to kicks, dashes, collisions, ...) is spread linearly over the cycle.
+pub fn decay_with_velocity(p0: (f32,f32), p1: (f32,f32), v0: (f32,f32), decay: f32, t: f32) -> (f32,f32) {
+    let g = decay_distance(decay, t);
+    let ex = p1.0 - p0.0 - v0.0;
+    let ey = p1.1 - p0.1 - v0.1;
+    return (p0.0 + v0.0*g + ex*t, p0.1 + v0.1*g + ey*t);
+}
+
+/// Position at t (0.0-1.0) wrt a movement from p0 to p1 spread over ncycles, for
+/// a object which is set moving at p0 and whose velocity decays by the given factor
+/// every cycle (like a kicked ball), so that it slows down as it nears p1.
+pub fn decay_over_cycles(p0: (f32,f32), p1: (f32,f32), decay: f32, ncycles: f32, t: f32) -> (f32,f32) {
+    let ncycles = ncycles.max(1.0);
+    let ratio = decay_distance(decay, t*ncycles)/decay_distance(decay, ncycles);
+    return linear(p0, p1, ratio);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn near(a: (f32,f32), b: (f32,f32)) -> bool {
+        return (a.0-b.0).abs() < 0.0001 && (a.1-b.1).abs() < 0.0001;
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_ends() {
+        let (pm1, p0, p1, p2) = ((0.0, 0.0), (1.0, 2.0), (3.0, 1.0), (4.0, 4.0));
+        assert!(near(catmull_rom(pm1, p0, p1, p2, 0.0), p0));
+        assert!(near(catmull_rom(pm1, p0, p1, p2, 1.0), p1));
+    }
+
+    #[test]
+    fn catmull_rom_evenly_spaced_is_linear() {
+        let (pm1, p0, p1, p2) = ((0.0, 0.0), (1.0, 2.0), (2.0, 4.0), (3.0, 6.0));
+        for t in [0.25, 0.5, 0.75] {
+            assert!(near(catmull_rom(pm1, p0, p1, p2, t), linear(p0, p1, t)));
+        }
+    }
+
+    #[test]
+    fn catmull_rom_curves_towards_neighbours() {
+        // Midpoint pulled away from the chord, wrt neighbours on the same side
+        let p = catmull_rom((0.0, 1.0), (1.0, 0.0), (2.0, 0.0), (3.0, 1.0), 0.5);
+        assert!(near(p, (1.5, -0.125)));
+    }
+
+}
@@ -3,12 +3,20 @@
 //! HanishKVC, 2022
 //!
 
+use loggerk::{ldebug, log_d};
 use sdl2::pixels::Color;
 use sdl2::ttf::Font;
 
-use crate::sdlx::{SdlX, XRect};
-use crate::playdata::{PlayUpdate, GameState};
+use crate::sdlx::{self, SdlX, XRect};
+use crate::config::Config;
+use crate::playdata::{PlayUpdate, GameState, PlayerCodedData, PlayerData, Action};
 use crate::proc::actions::{ActionsInfo, ActionData, AIAction};
+use crate::proc::events::GameEvents;
+use crate::proc::heatmaps::{Heatmaps, HeatmapTarget};
+use crate::proc::pitchcontrol::PitchControl;
+use crate::proc::offside::Offside;
+use crate::proc::shape::TeamShapes;
+use crate::proc::markers::ActionMarkers;
 
 pub const SIDE_L: char = 'l';
 pub const SIDE_R: char = 'r';
@@ -16,8 +24,8 @@ pub const XPLAYERID_START: usize = 0x1000;
 pub const XPLAYERID_UNKNOWN: usize = 0x1001;
 pub const XPLAYERID_OOPS_OTHERSIDE_START: usize = 0x8000;
 
-const ENTITY_WIDTH: u32 = 16;
-const ENTITY_HEIGHT: u32 = 16;
+pub const ENTITY_WIDTH: u32 = 16;
+pub const ENTITY_HEIGHT: u32 = 16;
 
 pub const BASE_SCREEN_WIDTH: u32 = 1024;
 pub const BASE_SCREEN_HEIGHT: u32 = 600;
@@ -25,10 +33,13 @@ pub const SCREEN_COLOR_BG: Color = Color::RGB(20, 200, 20);
 
 pub const FRAMES_PER_SEC: usize = 24;
 
-pub const PITCH_RECT: XRect = ((0.03,0.04), (0.97,0.96));
+pub const TIMELINE_RECT: XRect = ((0.03,0.962), (0.97,0.974));
+pub const EVENTLOG_RECT: XRect = ((0.74,0.08), (0.99,0.56));
+pub const EVENTLOG_NLINES: usize = 14;
+pub const PALETTE_RECT: XRect = ((0.2,0.15), (0.8,0.75));
+pub const PALETTE_NLINES: usize = 16;
 
 pub const MSG_SCORE_POS: (f32,f32)      = (0.01,0.01);
-pub const MSG_STIME_POS: (f32,f32)      = (0.50,0.01);
 pub const MSG_FPS_POS: (f32,f32)        = (0.80,0.01);
 pub const MSG_GAME_POS: (f32,f32)       = (0.01,0.98);
 pub const MSG_UNKNOWN_POS: (f32,f32)    = (0.50,0.98);
@@ -36,12 +47,24 @@ pub const MSG_TIMED_POS: (f32, f32)     = (0.01,0.08);
 
 const MSG_TIMED_NUMFRAMES: isize = 40;
 
-pub fn screen_color_bg_rel(r: u8, g: u8, b: u8) -> Color {
+/// The color and number of frames, wrt highlighting attackers in offside position at a kick
+const OFFSIDE_HIGHLIGHT_COLOR: Color = Color::MAGENTA;
+const OFFSIDE_HIGHLIGHT_NUMFRAMES: isize = 48;
+
+/// The number of recent actions listed, wrt the player details
+const INSPECT_RECENT_ACTIONS: usize = 5;
+
+/// The alpha wrt the trails behind the players and the ball, which are in the
+/// colors of the respective team and the ball.
+const TRAIL_ALPHA_TEAM: u8 = 160;
+const TRAIL_ALPHA_BALL: u8 = 200;
+
+pub fn screen_color_bg_rel(bg: Color, r: u8, g: u8, b: u8) -> Color {
     Color {
-        r: SCREEN_COLOR_BG.r.saturating_add(r),
-        g: SCREEN_COLOR_BG.g.saturating_add(g),
-        b: SCREEN_COLOR_BG.b.saturating_add(b),
-        a: SCREEN_COLOR_BG.a,
+        r: bg.r.saturating_add(r),
+        g: bg.g.saturating_add(g),
+        b: bg.b.saturating_add(b),
+        a: bg.a,
     }
 }
 
@@ -54,6 +77,15 @@ use objects::Ball;
 use objects::FixedPosMessage;
 pub mod simobjs;
 use simobjs::VirtBall;
+pub mod timeline;
+pub mod eventlog;
+pub mod palette;
+pub mod scoreboard;
+use scoreboard::Scoreboard;
+pub mod pitch;
+use pitch::Pitch;
+pub mod interpolate;
+use interpolate::Interpolator;
 
 
 #[derive(Debug)]
@@ -61,8 +93,12 @@ use simobjs::VirtBall;
 pub(crate) struct PGEntities<'a> {
     /// The frames per second, wrt movements on the screen
     fps: f32,
+    /// The timecounter wrt the last update
+    timecounter: usize,
     /// The fixed position based messages on the screen
     vfpmsgs: Vec<FixedPosMessage>,
+    /// The team names, scores and match clock
+    pub scoreboard: Scoreboard,
     /// Whether to show the ball or not
     pub showball: bool,
     /// The ball in the playground
@@ -71,12 +107,24 @@ pub(crate) struct PGEntities<'a> {
     lteam: team::Team<'a>,
     /// The other team in the playground.
     rteam: team::Team<'a>,
-    /// The pitch boundry within the screen, in normalised 0.0-1.0 space.
-    pitch: XRect,
+    /// The pitch along with its markings.
+    pitch: Pitch,
     /// If extra pitch markers should be shown or not.
     pub showxtrapitchmarkers: bool,
     /// Info from Data
     pub actionsinfo: ActionsInfo,
+    /// Game events like play mode changes
+    pub gameevents: GameEvents,
+    /// Positional occupancy of the ball and players
+    pub heatmaps: Heatmaps,
+    /// Territory controlled by each team
+    pub pitchcontrol: PitchControl,
+    /// Offside lines and checks
+    pub offside: Offside,
+    /// Shape metrics of the teams over time
+    pub shapes: TeamShapes,
+    /// Markers where the significant actions happened
+    pub markers: ActionMarkers,
     /// A virtual interpolated ball
     /// The graphical object representing virtual ball on the screen
     virtballg: Ball<'a>,
@@ -84,46 +132,64 @@ pub(crate) struct PGEntities<'a> {
     virtballd: Option<VirtBall>,
     /// Timed Msg
     pub timedmsg: FixedPosMessage,
+    /// The number of positions (records) remembered wrt the trails
+    trail_npoints: usize,
+    /// Whether to show the trails behind the ball and players or not
+    bshowtrails: bool,
+    /// The trail colors wrt lteam, rteam and ball
+    trail_colors: (Color, Color, Color),
 }
 
 impl<'a> PGEntities<'a> {
 
     /// Create a playground instance with
-    /// * pitch: the dimensions of the pitch with in the screen,
-    ///   in normalised 0.0-1.0 space.
     /// * [l/r]nplayers: the number of players on both sides.
-    /// * font: the font used for creating the cached text image datas if any
+    /// * font: the font used for creating the cached text image datas if any,
+    ///   else the builtin bitmap font is used wrt the ids.
+    /// * config: the colors, sizes and message positions to use.
     ///
     /// The following fixed position messages are supported on the screen
-    /// * score: Give the current score, if any.
-    /// * stime: Provide any time related info wrt the game.
-    /// * game: show any game related messages.
-    pub fn new(pitch: XRect, lnplayers: i32, rnplayers: i32, fps: f32, font: &'a Font) -> PGEntities<'a> {
+    /// * game: show any game related messages. These are tracked wrt time
+    ///   (so that they can be reverted when seeking back), if the playdata
+    ///   source is seekable, refer set_seekable.
+    ///
+    /// The team names, scores and match clock are shown through the scoreboard.
+    pub fn new(lnplayers: i32, rnplayers: i32, fps: f32, font: Option<&'a Font>, config: &Config) -> PGEntities<'a> {
         let mut vfpmsgs = Vec::new();
-        let scoremsg = FixedPosMessage::new("score", MSG_SCORE_POS, false, -1);
-        vfpmsgs.push(scoremsg);
-        let mut stimemsg = FixedPosMessage::new("stime", MSG_STIME_POS, false, -1);
-        stimemsg.config(Some(true), None);
-        vfpmsgs.push(stimemsg);
-        let gamemsg = FixedPosMessage::new("game", MSG_GAME_POS, false, -1);
+        let gamemsg = FixedPosMessage::new("game", config.msg_game_pos, false, -1);
         vfpmsgs.push(gamemsg);
-        let unknownmsg = FixedPosMessage::new("unknown", MSG_UNKNOWN_POS, false, -1);
+        let unknownmsg = FixedPosMessage::new("unknown", config.msg_unknown_pos, false, -1);
         vfpmsgs.push(unknownmsg);
-        let mut timedmsg = FixedPosMessage::new("timedmsg", MSG_TIMED_POS, true, MSG_TIMED_NUMFRAMES);
+        let mut timedmsg = FixedPosMessage::new("timedmsg", config.msg_timed_pos, true, MSG_TIMED_NUMFRAMES);
         timedmsg.update_direct("");
         PGEntities {
             fps: fps,
+            timecounter: 0,
             vfpmsgs: vfpmsgs,
-            ball: Ball::new(font),
+            scoreboard: Scoreboard::new(config.msg_score_pos),
+            ball: Ball::new(config.ball_size, config.color_ball, font),
             showball: true,
-            virtballg: Ball::new(font),
+            virtballg: Ball::new(config.ball_size, config.color_ball, font),
             virtballd: None,
-            lteam: team::Team::new("lteam", Color::RED, lnplayers, font),
-            rteam: team::Team::new("rteam", Color::BLUE, rnplayers, font),
-            pitch: pitch,
+            lteam: team::Team::new("lteam", config.color_lteam, lnplayers, config.player_size, font),
+            rteam: team::Team::new("rteam", config.color_rteam, rnplayers, config.player_size, font),
+            pitch: Pitch::new(),
             showxtrapitchmarkers: true,
             actionsinfo: ActionsInfo::new(lnplayers as usize, rnplayers as usize),
+            gameevents: GameEvents::new(),
+            heatmaps: Heatmaps::new(),
+            pitchcontrol: PitchControl::new(),
+            offside: Offside::new(),
+            shapes: TeamShapes::new(),
+            markers: ActionMarkers::new(config.marker_window),
             timedmsg: timedmsg,
+            trail_npoints: 0,
+            bshowtrails: true,
+            trail_colors: (
+                sdlx::color_alpha(config.color_lteam, TRAIL_ALPHA_TEAM),
+                sdlx::color_alpha(config.color_rteam, TRAIL_ALPHA_TEAM),
+                sdlx::color_alpha(config.color_ball, TRAIL_ALPHA_BALL),
+            ),
         }
     }
 
@@ -131,6 +197,16 @@ impl<'a> PGEntities<'a> {
         return self.fps;
     }
 
+    /// The timecounter wrt the last update
+    pub fn timecounter(&self) -> usize {
+        return self.timecounter;
+    }
+
+    /// The number of players in the left and right teams
+    pub fn nplayers(&self) -> (usize, usize) {
+        return (self.lteam.nplayers(), self.rteam.nplayers());
+    }
+
     /// Allow one to increase or decrease the fps, relative to the current fps.
     pub fn fps_adjust(&mut self, ratio: f32) -> f32 {
         self.fps *= ratio;
@@ -148,27 +224,83 @@ impl<'a> PGEntities<'a> {
     ///   * inframes - specifies as to in how many frames the object should
     ///     be moved to the new location being specified.
     pub fn update(&mut self, pu: PlayUpdate, babsolute: bool, inframes: f32) {
+        self.timecounter = pu.timecounter;
+        self.gameevents.update(pu.timecounter, &pu.state);
+        self.scoreboard.update(pu.timecounter, &pu.teams);
         for fpmsg in &mut self.vfpmsgs {
-            fpmsg.update(&pu.msgs);
+            fpmsg.update(pu.timecounter, &pu.msgs);
         }
         if self.virtballd.is_some() {
             let virtball = self.virtballd.as_mut().unwrap();
             let bpos = virtball.next_record(pu.timecounter);
-            self.virtballg.update(bpos, babsolute, inframes);
+            self.virtballg.update(pu.timecounter, bpos, babsolute, inframes);
         }
-        self.ball.update(pu.ball, babsolute, inframes);
-        self.lteam.update(pu.timecounter, pu.lteamcoded, babsolute, inframes, &mut self.actionsinfo);
-        self.rteam.update(pu.timecounter, pu.rteamcoded, babsolute, inframes, &mut self.actionsinfo);
+        self.ball.update(pu.timecounter, pu.ball, babsolute, inframes);
+        self.ball.set_vel(pu.ballvel);
+        self.heatmaps.add(pu.timecounter, HeatmapTarget::Ball, pu.ball);
+        let lkickers = Self::kickers(&pu.lteamcoded);
+        let rkickers = Self::kickers(&pu.rteamcoded);
+        self.lteam.update(pu.timecounter, pu.lteamcoded, babsolute, inframes, &mut self.actionsinfo, &mut self.heatmaps);
+        self.rteam.update(pu.timecounter, pu.rteamcoded, babsolute, inframes, &mut self.actionsinfo, &mut self.heatmaps);
+        self.offside_check_kicks(pu.timecounter, SIDE_L, &lkickers, pu.ball);
+        self.offside_check_kicks(pu.timecounter, SIDE_R, &rkickers, pu.ball);
+        self.offside.update_lines(&self.lteam.positions(true), &self.rteam.positions(true), pu.ball);
+        self.heatmaps.update(pu.timecounter);
+        self.shapes.update(pu.timecounter, SIDE_L, &self.lteam.positions(true));
+        self.shapes.update(pu.timecounter, SIDE_R, &self.rteam.positions(true));
         match pu.state {
             GameState::Goal(side)=> {
                 self.actionsinfo.handle_action(ActionData::new(pu.timecounter, side, XPLAYERID_UNKNOWN, pu.ball, AIAction::Goal))
             },
+            GameState::Offside(side) => {
+                let msg = self.offside.check_playmode(pu.timecounter, side);
+                ldebug!(&format!("INFO:GPPGND:Entities:{}:{}", pu.timecounter, msg));
+                self.timedmsg.update_direct(&msg);
+            },
             _ => {
 
             }
         }
     }
 
+    /// The players who kicked the ball, as per the given players data
+    fn kickers(playersdata: &Vec<PlayerCodedData>) -> Vec<usize> {
+        let mut kickers = Vec::new();
+        for (playerid, vpd) in playersdata {
+            for pd in vpd {
+                if let PlayerData::Action(Action::Kick(true)) = pd {
+                    kickers.push(*playerid as usize);
+                }
+            }
+        }
+        return kickers;
+    }
+
+    /// Check for attackers in offside position wrt the kicks by the given side,
+    /// and highlight them.
+    fn offside_check_kicks(&mut self, timecounter: usize, side: char, kickers: &Vec<usize>, ball: (f32, f32)) {
+        for kicker in kickers {
+            let (attackers, defenders) = if side == SIDE_L {
+                (self.lteam.positions(true), self.rteam.positions(true))
+            } else {
+                (self.rteam.positions(true), self.lteam.positions(true))
+            };
+            let offenders = self.offside.check_kick(timecounter, side, *kicker, &attackers, &defenders, ball);
+            let team = if side == SIDE_L { &mut self.lteam } else { &mut self.rteam };
+            for pi in offenders {
+                team.player_highlight(pi, OFFSIDE_HIGHLIGHT_COLOR, OFFSIDE_HIGHLIGHT_NUMFRAMES);
+            }
+        }
+    }
+
+    /// Set the positions the ball and players will be moved to, after the current
+    /// interpolated movement, as got from the next record (if known).
+    pub fn update_next(&mut self, npu: &PlayUpdate) {
+        self.ball.set_move_next(Some(npu.ball));
+        self.lteam.set_moves_next(&npu.lteamcoded);
+        self.rteam.set_moves_next(&npu.rteamcoded);
+    }
+
     /// If using interpolated updating of object positions,
     /// request them to generate their next interpolated position.
     pub fn next_frame(&mut self) {
@@ -180,36 +312,155 @@ impl<'a> PGEntities<'a> {
         self.rteam.next_frame();
     }
 
-    /// Draw the pitch on the screen, along with the boundries and any markers.
-    fn draw_pitch(&self, sx: &mut SdlX) {
-        //let inbtwcolor = Color::RGB(230, 230, 230);
-        let inbtwcolor = Color::WHITE;
-        let ((nx1,ny1),(nx2,ny2)) = self.pitch;
-        sx.nn_thick_line(nx1, ny1, nx2, ny1, 0.002, Color::WHITE);
-        sx.nn_thick_line(nx1, ny1, nx1, ny2, 0.004, Color::WHITE);
-        sx.nn_thick_line(nx1, ny2, nx2, ny2, 0.002, Color::WHITE);
-        sx.nn_thick_line(nx2, ny1, nx2, ny2, 0.004, Color::WHITE);
-        sx.nn_line(0.50, 0.02, 0.50, 0.98, inbtwcolor); // Center vertical
-        let leftmidx = nx1-0.01;
-        sx.nn_line(leftmidx, 0.40, leftmidx, 0.60, inbtwcolor); // Left mid
-        let rightmidx = nx2+0.01;
-        sx.nn_line(rightmidx, 0.40, rightmidx, 0.60, inbtwcolor); // Right mid
-        // Additional markers
-        if self.showxtrapitchmarkers {
-            sx.nn_line(0.25, 0.48, 0.25, 0.52, inbtwcolor); // Horizontal left quarter
-            sx.nn_line(0.75, 0.48, 0.75, 0.52, inbtwcolor); // Horizontal right quarter
-            sx.nn_line(0.48, 0.25, 0.52, 0.25, inbtwcolor); // Vertical top quarter
-            sx.nn_line(0.48, 0.75, 0.52, 0.75, inbtwcolor); // Vertical bottom quarter
-            sx.nn_line(0.49, 0.50, 0.51, 0.50, inbtwcolor); // Center horizontal
+    /// Set the interpolator to use wrt interpolated movements, for all entities
+    pub fn set_interpolator(&mut self, interpolator: Interpolator) {
+        self.virtballg.set_interpolator(interpolator);
+        if self.virtballd.is_some() {
+            self.virtballd.as_mut().unwrap().set_interpolator(interpolator);
+        }
+        self.ball.set_interpolator(interpolator);
+        self.lteam.set_interpolator(interpolator);
+        self.rteam.set_interpolator(interpolator);
+    }
+
+    /// Set up the trails behind the ball and players, if they are to be shown.
+    fn trails_apply(&mut self) {
+        let npoints = if self.bshowtrails { self.trail_npoints } else { 0 };
+        self.lteam.trails_set(npoints, self.trail_colors.0);
+        self.rteam.trails_set(npoints, self.trail_colors.1);
+        self.ball.trail_set(npoints, self.trail_colors.2);
+    }
+
+    /// Set the length of the trails behind the ball and players, in terms of
+    /// the number of records (positions) remembered. 0 disables the trails.
+    pub fn set_trails(&mut self, npoints: usize) {
+        self.trail_npoints = npoints;
+        self.trails_apply();
+    }
+
+    pub fn toggle_bshowoffside(&mut self) {
+        self.offside.bshow = !self.offside.bshow;
+        if self.offside.bshow {
+            self.timedmsg.update_direct("Offside:Show");
+        } else {
+            self.timedmsg.update_direct("Offside:Hide");
+        }
+    }
+
+    pub fn toggle_bshowshapes(&mut self) {
+        self.shapes.bshow = !self.shapes.bshow;
+        if self.shapes.bshow {
+            self.timedmsg.update_direct("TeamShape:Show");
+        } else {
+            self.timedmsg.update_direct("TeamShape:Hide");
         }
     }
 
+    pub fn toggle_bshowmarkers(&mut self) {
+        self.markers.bshow = !self.markers.bshow;
+        if self.markers.bshow {
+            self.timedmsg.update_direct("ActionMarkers:Show");
+        } else {
+            self.timedmsg.update_direct("ActionMarkers:Hide");
+        }
+    }
+
+    /// Cycle the time window over which the action markers fade out
+    pub fn markers_window_cycle(&mut self) {
+        let window = self.markers.window_cycle();
+        if window == 0 {
+            self.timedmsg.update_direct("ActionMarkers:Window:All");
+        } else {
+            self.timedmsg.update_direct(&format!("ActionMarkers:Window:{}", window));
+        }
+    }
+
+    /// Cycle the action type, whose markers are shown
+    pub fn markers_action_cycle(&mut self) {
+        match self.markers.action_cycle() {
+            Some(action) => self.timedmsg.update_direct(&format!("ActionMarkers:Action:{}", action)),
+            None => self.timedmsg.update_direct("ActionMarkers:Action:All"),
+        }
+    }
+
+    /// Show the action markers of the given player alone, or else of all players
+    /// if they were already limited to the given player.
+    pub fn markers_player_toggle(&mut self, side: char, playerid: usize) {
+        if self.markers.player() == Some((side, playerid)) {
+            self.markers.set_player(None);
+            self.timedmsg.update_direct("ActionMarkers:Player:All");
+        } else {
+            self.markers.set_player(Some((side, playerid)));
+            self.timedmsg.update_direct(&format!("ActionMarkers:Player:{}{:02}", side, playerid));
+        }
+    }
+
+    /// Export the team shape metrics over time, into the given csv file
+    pub fn export_shapes(&mut self, fname: &str) {
+        match self.shapes.export_csv(fname) {
+            Ok(cnt) => {
+                eprintln!("INFO:GPPGND:Entities:ExportShapes:{}:{} records", fname, cnt);
+                self.timedmsg.update_direct(&format!("TeamShape:Exported:{}", fname));
+            },
+            Err(e) => {
+                eprintln!("WARN:GPPGND:Entities:ExportShapes:{}:{}", fname, e);
+                self.timedmsg.update_direct("TeamShape:ExportFailed");
+            },
+        }
+    }
+
+    pub fn pitchcontrol_cycle(&mut self) {
+        let mode = self.pitchcontrol.mode_cycle();
+        self.timedmsg.update_direct(&format!("PitchControl:{}", mode));
+    }
+
+    pub fn toggle_bshowtrails(&mut self) {
+        self.bshowtrails = !self.bshowtrails;
+        self.trails_apply();
+        if self.bshowtrails {
+            self.timedmsg.update_direct("Trails:Show");
+        } else {
+            self.timedmsg.update_direct("Trails:Hide");
+        }
+    }
+
+    /// Complete any pending interpolated movements immidiately.
+    /// Useful when switching from interpolated to discrete updating.
+    pub fn move_finish(&mut self) {
+        self.virtballg.move_finish();
+        self.ball.move_finish();
+        self.lteam.move_finish();
+        self.rteam.move_finish();
+    }
+
+    /// Update the overlays (which depend on the positions of the entities),
+    /// wrt any records processed since the last call. Called once per frame
+    /// before drawing, so that seeks, fast forwards, ... are also covered.
+    pub fn update_overlays(&mut self) {
+        let mut pcplayers = Vec::new();
+        self.lteam.pcplayers(&mut pcplayers);
+        self.rteam.pcplayers(&mut pcplayers);
+        self.pitchcontrol.update(pcplayers);
+    }
+
     /// Draw all the objects in the playground.
+    ///
+    /// The pitch and the moving objects are drawn in the world space (ie as seen
+    /// through the camera), while the messages are drawn in the screen space.
     pub fn draw(&mut self, sx: &mut SdlX) {
-        self.draw_pitch(sx);
+        sx.space_world();
+        self.pitch.draw(sx, self.showxtrapitchmarkers);
+        self.pitchcontrol.draw(sx, self.lteam.color(), self.rteam.color());
+        self.heatmaps.draw(sx);
+        self.offside.draw(sx, self.lteam.color(), self.rteam.color());
+        self.shapes.draw(sx, self.timecounter, self.lteam.color(), self.rteam.color());
+        self.markers.draw(sx, self.actionsinfo.actions(), self.timecounter, self.lteam.color(), self.rteam.color());
+        sx.space_screen();
         for fpmsg in &mut self.vfpmsgs {
             fpmsg.draw(sx);
         }
+        self.scoreboard.draw(sx, self.lteam.color(), self.rteam.color());
+        sx.space_world();
         self.lteam.draw(sx);
         self.rteam.draw(sx);
         if self.showball {
@@ -218,9 +469,62 @@ impl<'a> PGEntities<'a> {
         if self.virtballd.is_some() {
             self.virtballg.draw(sx);
         }
+        sx.space_screen();
         self.timedmsg.draw(sx);
     }
 
+    /// The current position of the ball, in normal space
+    pub fn ball_pos(&self) -> (f32, f32) {
+        return self.ball.pos();
+    }
+
+    /// The current position of the specified player, in normal space
+    pub fn player_pos(&self, side: char, playerid: usize) -> (f32, f32) {
+        if side == SIDE_L {
+            return self.lteam.player_pos(playerid);
+        }
+        return self.rteam.player_pos(playerid);
+    }
+
+    /// The color of the team wrt the specified side
+    pub fn team_color(&self, side: char) -> Color {
+        if side == SIDE_L {
+            return self.lteam.color();
+        }
+        return self.rteam.color();
+    }
+
+    /// The player nearest to the given position (in normal space), provided
+    /// it is within the given distance, as (side, playerid).
+    pub fn player_nearest(&self, npos: (f32, f32), maxdist: f32) -> Option<(char, usize)> {
+        let mut nearest = None;
+        let mut mindist = maxdist;
+        for (side, team) in [(SIDE_L, &self.lteam), (SIDE_R, &self.rteam)] {
+            for (pi, ppos) in team.positions(false).iter().enumerate() {
+                let dist = ((ppos.0-npos.0).powi(2) + (ppos.1-npos.1).powi(2)).sqrt();
+                if dist <= mindist {
+                    mindist = dist;
+                    nearest = Some((side, pi));
+                }
+            }
+        }
+        return nearest;
+    }
+
+    /// The details wrt the specified player, as a set of lines of text,
+    /// with the heading line first.
+    pub fn player_details(&self, side: char, playerid: usize, inc_cardscore: bool) -> Vec<String> {
+        let team = if side == SIDE_L { &self.lteam } else { &self.rteam };
+        let mut vdetails = vec![format!("Player {}{:02}", side, playerid)];
+        let sstamina = match team.player_stamina(playerid) {
+            Some(stamina) => format!("Stamina: {:.0}%", stamina*100.0),
+            None => "Stamina: Unknown".to_string(),
+        };
+        vdetails.push(sstamina);
+        vdetails.append(&mut self.actionsinfo.player_details(side, playerid, inc_cardscore, INSPECT_RECENT_ACTIONS));
+        return vdetails;
+    }
+
 }
 
 impl<'a> PGEntities<'a> {
@@ -264,11 +568,60 @@ impl<'a> PGEntities<'a> {
         }
     }
 
+    pub fn toggle_bshowbody(&mut self) {
+        let lshow = self.lteam.toggle_bshowbody();
+        let rshow = self.rteam.toggle_bshowbody();
+        if lshow && rshow {
+            self.timedmsg.update_direct("Body:Show");
+        } else {
+            self.timedmsg.update_direct("Body:Hide");
+        }
+    }
+
+    pub fn toggle_bshowneck(&mut self) {
+        let lshow = self.lteam.toggle_bshowneck();
+        let rshow = self.rteam.toggle_bshowneck();
+        if lshow && rshow {
+            self.timedmsg.update_direct("Neck:Show");
+        } else {
+            self.timedmsg.update_direct("Neck:Hide");
+        }
+    }
+
+    pub fn toggle_bshowviewcone(&mut self) {
+        let lshow = self.lteam.toggle_bshowviewcone();
+        let rshow = self.rteam.toggle_bshowviewcone();
+        if lshow && rshow {
+            self.timedmsg.update_direct("ViewCone:Show");
+        } else {
+            self.timedmsg.update_direct("ViewCone:Hide");
+        }
+    }
+
+    /// Inform whether the playdata source supports seeking, so that the state
+    /// maintained only wrt seeking back, is avoided if not needed.
+    pub fn set_seekable(&mut self, bseekable: bool) {
+        self.actionsinfo.set_checkpoints(bseekable);
+        for fpmsg in &mut self.vfpmsgs {
+            if fpmsg.key() == "game" {
+                fpmsg.set_history(bseekable);
+            }
+        }
+    }
+
     pub fn seek(&mut self, seekdelta: isize) {
         if self.virtballd.is_some() {
             self.virtballd.as_mut().unwrap().seek(seekdelta);
         }
         self.actionsinfo.seek(seekdelta);
+        self.gameevents.seek();
+        self.heatmaps.seek();
+        self.offside.seek();
+        self.shapes.seek();
+        self.scoreboard.seek();
+        for fpmsg in &mut self.vfpmsgs {
+            fpmsg.seek();
+        }
     }
 
     pub fn save_virtball_csv(&mut self) {
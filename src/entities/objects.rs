@@ -8,7 +8,9 @@ use sdl2::ttf::Font;
 
 use crate::sdlx::SdlX;
 use crate::playdata::Messages;
+use crate::playdata::rcss;
 use crate::entities::gentity::GEntity;
+use crate::entities::interpolate::Interpolator;
 
 pub const BALL_SIZE: u32 = 6;
 pub const BALL_COLOR: Color = Color::WHITE;
@@ -29,9 +31,9 @@ impl<'a> Ball<'a> {
 
     /// Create a new instance of the ball.
     /// NOTE: The position is set using update call.
-    pub fn new(font: &'a Font) -> Ball<'a> {
+    pub fn new(size: u32, color: Color, font: Option<&'a Font>) -> Ball<'a> {
         Ball {
-            bge: GEntity::new(" ", (0.0,0.0), (BALL_SIZE, BALL_SIZE), BALL_COLOR, font)
+            bge: GEntity::new(" ", (0.0,0.0), (size, size), color, font)
         }
     }
 
@@ -47,7 +49,7 @@ impl<'a> Ball<'a> {
     ///   to the given position.
     ///   * inframes - specifies as to in how many frames the ball should
     ///     be moved to the new location being specified.
-    pub fn update(&mut self, pos: (f32,f32), babsolute: bool, inframes: f32) {
+    pub fn update(&mut self, timecounter: usize, pos: (f32,f32), babsolute: bool, inframes: f32) {
         let fx = pos.0;
         let fy = pos.1;
         if babsolute {
@@ -55,12 +57,41 @@ impl<'a> Ball<'a> {
         } else {
             self.bge.move_to_in_frames((fx, fy), inframes);
         }
+        self.bge.trail_add(timecounter, pos);
+    }
+
+    /// Set the position the ball will be moved to after the current movement, if known
+    pub fn set_move_next(&mut self, npos: Option<(f32,f32)>) {
+        self.bge.set_move_next(npos);
     }
 
     pub fn next_frame(&mut self) {
         self.bge.next_frame();
     }
 
+    pub fn move_finish(&mut self) {
+        self.bge.move_finish();
+    }
+
+    /// Set up the trail behind the ball, refer GEntity::trail_set
+    pub fn trail_set(&mut self, maxpoints: usize, color: Color) {
+        self.bge.trail_set(maxpoints, color);
+    }
+
+    /// Set the velocity (per record) of the ball, if known
+    pub fn set_vel(&mut self, vel: Option<(f32,f32)>) {
+        self.bge.set_vel(vel, rcss::BALL_DECAY);
+    }
+
+    pub fn set_interpolator(&mut self, interpolator: Interpolator) {
+        self.bge.set_interpolator(interpolator);
+    }
+
+    /// The current position of the ball, in normal space
+    pub fn pos(&self) -> (f32, f32) {
+        return self.bge.pos();
+    }
+
     pub fn draw(&mut self, sx: &mut SdlX) {
         self.bge.draw(sx);
     }
@@ -74,6 +105,9 @@ impl<'a> Ball<'a> {
 ///
 /// The message will remain on the screen till either a new message is
 /// provided using update, or the optional autoclear kicks in.
+///
+/// Optionally the messages got through update, can be tracked wrt time,
+/// so that the message shown can be reverted back, when seeking back.
 pub struct FixedPosMessage {
     /// Key used to identify any new message in the hashmap of messages
     /// provided during update call.
@@ -93,6 +127,12 @@ pub struct FixedPosMessage {
     autoclearchk: isize,
     /// Track the remaining frames wrt autoclear logic
     autoclearcnt: isize,
+    /// Whether to track the messages wrt time
+    bhistory: bool,
+    /// The messages got till now, as (timecounter, message)
+    history: Vec<(usize, String)>,
+    /// Flag to indicate a seek was requested
+    handle_deferedseek: bool,
 }
 
 impl FixedPosMessage {
@@ -108,9 +148,30 @@ impl FixedPosMessage {
             autoclearchk: autoclearchk,
             autoclearcnt: autoclearchk,
             posmid: false,
+            bhistory: false,
+            history: Vec::new(),
+            handle_deferedseek: false,
         }
     }
 
+    /// The key used to identify the message wrt this
+    pub fn key(&self) -> &str {
+        return &self.key;
+    }
+
+    /// Control whether the messages got through update are tracked wrt time,
+    /// so that the message shown can be reverted back wrt seeking.
+    ///
+    /// As the history isnt trimmed, other than when seeking back, this is
+    /// meant only for playdata sources which support seeking.
+    pub fn set_history(&mut self, bhistory: bool) {
+        self.bhistory = bhistory;
+        if !bhistory {
+            self.history.clear();
+        }
+    }
+
+    #[allow(dead_code)]
     pub fn config(&mut self, posmid: Option<bool>, color: Option<Color>) {
         if posmid.is_some() {
             self.posmid = posmid.unwrap();
@@ -131,15 +192,40 @@ impl FixedPosMessage {
 
     /// Pass a hashmap of messages from which the message, if any,
     /// is picked up using the key setup during new.
-    pub fn update(&mut self, msgs: &Messages) {
+    ///
+    /// If history is enabled, and a seek was requested, the messages at or
+    /// beyond the given timecounter are dropped, and the latest message
+    /// before it is shown, unless there is a new message.
+    pub fn update(&mut self, timecounter: usize, msgs: &Messages) {
+        if self.bhistory && self.handle_deferedseek {
+            while self.history.len() > 0 && self.history[self.history.len()-1].0 >= timecounter {
+                self.history.pop();
+            }
+            let msg = match self.history.last() {
+                Some(hmsg) => hmsg.1.clone(),
+                None => self.key.clone(),
+            };
+            self.update_direct(&msg);
+        }
+        self.handle_deferedseek = false;
         let msg = msgs.get(&self.key);
         if msg.is_none() {
             return;
         }
         let msg = msg.unwrap();
+        // Sources may repeat the same message wrt each record, so only changes are noted
+        if self.bhistory && (self.history.last().map(|hmsg| &hmsg.1) != Some(msg)) {
+            self.history.push((timecounter, msg.to_string()));
+        }
         self.update_direct(msg);
     }
 
+    /// The message shown is reverted (if history is enabled) as required,
+    /// when the next update occurs.
+    pub fn seek(&mut self) {
+        self.handle_deferedseek = true;
+    }
+
     /// Draw the message on the screen.
     ///
     /// If autoclear is set, then it keeps track of autoclear count
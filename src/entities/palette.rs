@@ -0,0 +1,109 @@
+//!
+//! A searchable command palette
+//! HanishKVC, 2022
+//!
+
+use sdl2::pixels::Color;
+use sdl2::render::BlendMode;
+
+use crate::sdlx::{SdlX, XRect};
+use crate::keys::{KeyMap, ProgramEvent};
+
+const PALETTE_COLOR_BG: Color = Color::RGBA(20, 20, 60, 220);
+const PALETTE_COLOR_QUERY: Color = Color::YELLOW;
+const PALETTE_COLOR_TEXT: Color = Color::WHITE;
+const PALETTE_COLOR_SELECTED_BG: Color = Color::RGBA(80, 80, 160, 220);
+
+
+#[derive(Debug)]
+/// Show a overlay listing the commands (with their key sequences), which match
+/// the text typed in. The selected command can be run, without having to
+/// remember its key sequence.
+pub struct Palette {
+    /// Whether the palette is shown or not
+    pub bshow: bool,
+    /// The overlay on the screen, in normalised 0.0-1.0 space.
+    nrect: XRect,
+    /// The number of matching commands shown at a time
+    nlines: usize,
+    /// The text typed in, to search the commands with
+    query: String,
+    /// The index of the selected command, among the matching commands
+    selected: usize,
+}
+
+impl Palette {
+
+    pub fn new(nrect: XRect, nlines: usize) -> Palette {
+        Palette {
+            bshow: false,
+            nrect: nrect,
+            nlines: nlines,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Show the palette, with a fresh search
+    pub fn open(&mut self) {
+        self.bshow = true;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.bshow = false;
+    }
+
+    pub fn input(&mut self, text: &str) {
+        self.query.push_str(text);
+        self.selected = 0;
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.selected = 0;
+    }
+
+    /// Move the selection by the given delta, staying within the matching commands
+    pub fn move_by(&mut self, delta: isize, keymap: &KeyMap) {
+        let nfound = keymap.search(&self.query).len();
+        if nfound == 0 {
+            self.selected = 0;
+            return;
+        }
+        self.selected = (self.selected as isize + delta).clamp(0, nfound as isize - 1) as usize;
+    }
+
+    /// Close the palette and return the program event of the selected command, if any.
+    pub fn run(&mut self, keymap: &KeyMap) -> Option<ProgramEvent> {
+        self.close();
+        let found = keymap.search(&self.query);
+        return found.get(self.selected).map(|f| f.1.clone()).filter(|pev| !matches!(pev, ProgramEvent::CommandPalette));
+    }
+
+    /// Draw the palette, with the search text followed by the matching commands
+    /// around the selected one.
+    pub fn draw(&self, sx: &mut SdlX, keymap: &KeyMap) {
+        if !self.bshow {
+            return;
+        }
+        let ((nx1,ny1),(nx2,ny2)) = self.nrect;
+        let nlh = (ny2-ny1)/(self.nlines + 2) as f32;
+        sx.wc.set_blend_mode(BlendMode::Blend);
+        sx.wc.set_draw_color(PALETTE_COLOR_BG);
+        sx.nn_fill_rect(nx1, ny1, nx2-nx1, ny2-ny1);
+        let found = keymap.search(&self.query);
+        sx.n_string(nx1+0.01, ny1+nlh*0.6, &format!("> {}_  [{}]", self.query, found.len()), PALETTE_COLOR_QUERY);
+        let start = (self.selected + 1).saturating_sub(self.nlines);
+        for (i, (line, _pev)) in found.iter().enumerate().skip(start).take(self.nlines) {
+            let ny = ny1 + nlh*((i-start) as f32 + 2.0);
+            if i == self.selected {
+                sx.wc.set_draw_color(PALETTE_COLOR_SELECTED_BG);
+                sx.nn_fill_rect(nx1, ny, nx2-nx1, nlh);
+            }
+            sx.n_string(nx1+0.01, ny+nlh*0.2, line, PALETTE_COLOR_TEXT);
+        }
+    }
+
+}
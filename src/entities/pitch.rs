@@ -0,0 +1,133 @@
+//!
+//! The rcss pitch and its markings
+//! HanishKVC, 2022
+//!
+
+use sdl2::pixels::Color;
+
+use crate::sdlx::{SdlX, XPoint, XSpaces};
+use crate::playdata::rcss;
+
+const PITCH_COLOR_LINES: Color = Color::WHITE;
+/// Width of the boundry lines, in normal space
+const PITCH_NW_BOUNDRY: f32 = 0.003;
+/// Number of line segments used wrt a full circle
+const CIRCLE_SEGMENTS: usize = 48;
+
+
+#[derive(Debug)]
+/// The pitch along with its markings, as per the rcss field dimensions.
+///
+/// The markings are specified in the rcss space (in meters) and converted
+/// to the normal 0.0-1.0 space, using the same XSpaces conversion used
+/// wrt the player and ball positions got from the rcss playdata sources.
+pub struct Pitch {
+    /// Conversion from rcss space to normal space
+    r2n: XSpaces,
+}
+
+impl Pitch {
+
+    pub fn new() -> Pitch {
+        Pitch {
+            r2n: rcss::r2n(),
+        }
+    }
+
+    /// Draw a line between the given points in rcss space
+    fn line(&self, sx: &mut SdlX, r1: XPoint, r2: XPoint) {
+        let (nx1, ny1) = self.r2n.d2o(r1);
+        let (nx2, ny2) = self.r2n.d2o(r2);
+        sx.nn_line(nx1, ny1, nx2, ny2, PITCH_COLOR_LINES);
+    }
+
+    /// Draw a rectangle given its top-left and bottom-right corners in rcss space
+    fn rect(&self, sx: &mut SdlX, r1: XPoint, r2: XPoint) {
+        self.line(sx, (r1.0, r1.1), (r2.0, r1.1));
+        self.line(sx, (r2.0, r1.1), (r2.0, r2.1));
+        self.line(sx, (r2.0, r2.1), (r1.0, r2.1));
+        self.line(sx, (r1.0, r2.1), (r1.0, r1.1));
+    }
+
+    /// Draw a arc (in rcss space) as a series of line segments, so that it remains
+    /// in sync with the rcss to normal space conversion, along both the axis.
+    /// The angles are in degrees, with 0 along +ve x-axis and 90 along +ve y-axis.
+    fn arc(&self, sx: &mut SdlX, rc: XPoint, rrad: f32, startangle: f32, endangle: f32) {
+        let nsegs = ((CIRCLE_SEGMENTS as f32 * (endangle-startangle).abs()/360.0).ceil() as usize).max(2);
+        let mut rprev = (0.0, 0.0);
+        for i in 0..=nsegs {
+            let angle = (startangle + (endangle-startangle)*(i as f32/nsegs as f32)).to_radians();
+            let rcur = (rc.0 + rrad*angle.cos(), rc.1 + rrad*angle.sin());
+            if i > 0 {
+                self.line(sx, rprev, rcur);
+            }
+            rprev = rcur;
+        }
+    }
+
+    /// Draw a small filled spot at the given point in rcss space
+    fn spot(&self, sx: &mut SdlX, rc: XPoint) {
+        let (nx, ny) = self.r2n.d2o(rc);
+        sx.wc.set_draw_color(PITCH_COLOR_LINES);
+        sx.ns_fill_rect_mid(nx, ny, 3, 3);
+    }
+
+    /// Draw the pitch markings.
+    ///
+    /// showxtra: if true, show the quarter markers along the axis.
+    pub fn draw(&self, sx: &mut SdlX, showxtra: bool) {
+        let hl = rcss::PITCH_LENGTH/2.0;
+        let hw = rcss::PITCH_WIDTH/2.0;
+        // Boundry
+        let (nx1, ny1) = self.r2n.d2o((-hl, -hw));
+        let (nx2, ny2) = self.r2n.d2o((hl, hw));
+        sx.nn_thick_line(nx1, ny1, nx2, ny1, PITCH_NW_BOUNDRY, PITCH_COLOR_LINES);
+        sx.nn_thick_line(nx1, ny2, nx2, ny2, PITCH_NW_BOUNDRY, PITCH_COLOR_LINES);
+        sx.nn_thick_line(nx1, ny1, nx1, ny2, PITCH_NW_BOUNDRY, PITCH_COLOR_LINES);
+        sx.nn_thick_line(nx2, ny1, nx2, ny2, PITCH_NW_BOUNDRY, PITCH_COLOR_LINES);
+        // Center line, circle and spot
+        self.line(sx, (0.0, -hw), (0.0, hw));
+        self.arc(sx, (0.0, 0.0), rcss::CENTER_CIRCLE_RADIUS, 0.0, 360.0);
+        self.spot(sx, (0.0, 0.0));
+        // Penalty and goal areas, penalty spots and arcs, goals
+        let hpaw = rcss::PENALTY_AREA_WIDTH/2.0;
+        let hgaw = rcss::GOAL_AREA_WIDTH/2.0;
+        let hgw = rcss::GOAL_WIDTH/2.0;
+        // The angle at which the penalty arc meets the penalty area line
+        let parcangle = ((rcss::PENALTY_AREA_LENGTH - rcss::PENALTY_SPOT_DIST)/rcss::CENTER_CIRCLE_RADIUS).acos().to_degrees();
+        for dir in [-1.0f32, 1.0] {
+            let gx = dir*hl;
+            let pax = dir*(hl - rcss::PENALTY_AREA_LENGTH);
+            let gax = dir*(hl - rcss::GOAL_AREA_LENGTH);
+            self.rect(sx, (pax.min(gx), -hpaw), (pax.max(gx), hpaw));
+            self.rect(sx, (gax.min(gx), -hgaw), (gax.max(gx), hgaw));
+            let spotx = dir*(hl - rcss::PENALTY_SPOT_DIST);
+            self.spot(sx, (spotx, 0.0));
+            let arcbase = if dir < 0.0 { 0.0 } else { 180.0 };
+            self.arc(sx, (spotx, 0.0), rcss::CENTER_CIRCLE_RADIUS, arcbase-parcangle, arcbase+parcangle);
+            // Goal mouth, outside the goal line
+            let gdx = dir*(hl + rcss::GOAL_DEPTH);
+            let (ngx1, ngy1) = self.r2n.d2o((gx.min(gdx), -hgw));
+            let (ngx2, ngy2) = self.r2n.d2o((gx.max(gdx), hgw));
+            sx.nn_thick_line(ngx1, ngy1, ngx2, ngy1, PITCH_NW_BOUNDRY, PITCH_COLOR_LINES);
+            sx.nn_thick_line(ngx1, ngy2, ngx2, ngy2, PITCH_NW_BOUNDRY, PITCH_COLOR_LINES);
+            let ngdx = if dir < 0.0 { ngx1 } else { ngx2 };
+            sx.nn_thick_line(ngdx, ngy1, ngdx, ngy2, PITCH_NW_BOUNDRY, PITCH_COLOR_LINES);
+        }
+        // Corner arcs
+        self.arc(sx, (-hl, -hw), rcss::CORNER_ARC_RADIUS, 0.0, 90.0);
+        self.arc(sx, (hl, -hw), rcss::CORNER_ARC_RADIUS, 90.0, 180.0);
+        self.arc(sx, (hl, hw), rcss::CORNER_ARC_RADIUS, 180.0, 270.0);
+        self.arc(sx, (-hl, hw), rcss::CORNER_ARC_RADIUS, 270.0, 360.0);
+        // Additional markers
+        if showxtra {
+            let qx = hl/2.0;
+            let qy = hw/2.0;
+            self.line(sx, (-qx, -1.0), (-qx, 1.0)); // Horizontal left quarter
+            self.line(sx, (qx, -1.0), (qx, 1.0)); // Horizontal right quarter
+            self.line(sx, (-1.0, -qy), (1.0, -qy)); // Vertical top quarter
+            self.line(sx, (-1.0, qy), (1.0, qy)); // Vertical bottom quarter
+        }
+    }
+
+}
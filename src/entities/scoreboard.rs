@@ -0,0 +1,121 @@
+//!
+//! A scoreboard wrt the game
+//! HanishKVC, 2022
+//!
+
+use sdl2::pixels::Color;
+use sdl2::render::BlendMode;
+
+use crate::sdlx::{self, SdlX};
+use crate::playdata::{TeamsInfo, rcss};
+
+const SCOREBOARD_COLOR_TEXT: Color = Color::BLACK;
+const SCOREBOARD_COLOR_BG: Color = sdlx::COLOR_MSG_BOX_BACKGROUND;
+
+
+#[derive(Debug)]
+/// Show the team names and scores (in the team colors), along with the match
+/// clock (mm:ss) and the half.
+///
+/// The team info got is tracked wrt time, so that the scoreboard can be
+/// reverted back, when seeking back.
+pub struct Scoreboard {
+    /// The position (top-left) of the scoreboard, in normal space
+    npos: (f32, f32),
+    /// The team info got till now, as (timecounter, teamsinfo)
+    history: Vec<(usize, TeamsInfo)>,
+    /// The current timecounter
+    timecounter: usize,
+    /// The seconds between records, wrt converting the timecounter to clock
+    seconds_per_record: f32,
+    /// Flag to indicate a seek was requested
+    handle_deferedseek: bool,
+}
+
+impl Scoreboard {
+
+    pub fn new(npos: (f32, f32)) -> Scoreboard {
+        Scoreboard {
+            npos: npos,
+            history: Vec::new(),
+            timecounter: 0,
+            seconds_per_record: rcss::SECONDS_PER_RECORD,
+            handle_deferedseek: false,
+        }
+    }
+
+    pub fn set_seconds_per_record(&mut self, spr: f32) {
+        self.seconds_per_record = spr;
+    }
+
+    /// Update the time and the team info (if any) wrt the given timecounter.
+    ///
+    /// If a seek was requested, the team info at or beyond the given timecounter
+    /// is dropped first.
+    pub fn update(&mut self, timecounter: usize, teams: &Option<TeamsInfo>) {
+        if self.handle_deferedseek {
+            while self.history.len() > 0 && self.history[self.history.len()-1].0 >= timecounter {
+                self.history.pop();
+            }
+            self.handle_deferedseek = false;
+        }
+        self.timecounter = timecounter;
+        if let Some(teams) = teams {
+            if self.history.last().map(|h| &h.1) != Some(teams) {
+                self.history.push((timecounter, teams.clone()));
+            }
+        }
+    }
+
+    pub fn seek(&mut self) {
+        self.handle_deferedseek = true;
+    }
+
+    /// The half (1H, 2H or ET for extra time) and the match clock (mm:ss)
+    fn clock(&self) -> (&str, String) {
+        let secs = self.timecounter as f32 * self.seconds_per_record;
+        let half = if secs <= rcss::HALF_TIME_SECONDS {
+            "1H"
+        } else if secs <= rcss::HALF_TIME_SECONDS*2.0 {
+            "2H"
+        } else {
+            "ET"
+        };
+        let isecs = secs.floor() as usize;
+        return (half, format!("{:02}:{:02}", isecs/60, isecs%60));
+    }
+
+    /// Draw the scoreboard, like  lname 1 - 0 rname  1H 12:34
+    pub fn draw(&self, sx: &mut SdlX, lcolor: Color, rcolor: Color) {
+        let (lname, rname, lscore, rscore) = match self.history.last() {
+            Some((_, teams)) => (teams.lname.as_str(), teams.rname.as_str(), teams.lscore, teams.rscore),
+            None => ("lteam", "rteam", 0, 0),
+        };
+        let (half, clock) = self.clock();
+        let sl = format!("{} {}", lname, lscore);
+        let sm = " - ";
+        let sr = format!("{} {}", rscore, rname);
+        let st = format!("  {} {}", half, clock);
+        let cw = sx.n2s.o2dx_len(sdlx::STRING_CHAR_PIXEL_WIDTH);
+        let ch = sx.n2s.o2dy_len(sdlx::STRING_CHAR_PIXEL_HEIGHT);
+        let (nx, ny) = self.npos;
+        let nchars = sl.chars().count() + sm.chars().count() + sr.chars().count() + st.chars().count() + 2;
+        sx.wc.set_blend_mode(BlendMode::Blend);
+        sx.wc.set_draw_color(SCOREBOARD_COLOR_BG);
+        sx.nn_fill_rect(nx-cw, ny-ch*0.5, nchars as f32*cw, ch*2.0);
+        // Team color tabs at either end
+        sx.wc.set_draw_color(lcolor);
+        sx.nn_fill_rect(nx-cw, ny-ch*0.5, cw*0.5, ch*2.0);
+        let mut cx = nx;
+        sx.n_string(cx, ny, &sl, lcolor);
+        cx += sl.chars().count() as f32*cw;
+        sx.n_string(cx, ny, sm, SCOREBOARD_COLOR_TEXT);
+        cx += sm.chars().count() as f32*cw;
+        sx.n_string(cx, ny, &sr, rcolor);
+        cx += sr.chars().count() as f32*cw;
+        sx.wc.set_draw_color(rcolor);
+        sx.nn_fill_rect(cx+cw*0.25, ny-ch*0.5, cw*0.5, ch*2.0);
+        sx.n_string(cx, ny, &st, SCOREBOARD_COLOR_TEXT);
+    }
+
+}
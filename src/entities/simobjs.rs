@@ -5,6 +5,9 @@
 
 use loggerk::{ldebug, log_d};
 
+use crate::playdata::rcss;
+use crate::entities::interpolate::{self, Interpolator};
+
 #[derive(Debug)]
 /// A interpolated ball
 pub struct VirtBall {
@@ -16,16 +19,20 @@ pub struct VirtBall {
     ltime: usize,
     /// Starting position of next action/ball movement change
     lpos: (f32, f32),
+    /// Position wrt the action/ball movement change before the current segment
+    ppos: (f32, f32),
+    /// Time and position of the ball, at the start of the current segment
+    stime: usize,
+    spos: (f32, f32),
+    /// The interpolator used wrt the current segment
+    interpolator: Interpolator,
     /// Position of the ball as it stands now
     cpos: (f32, f32),
-    /// Base amount of change to apply wrt ball position, per step
+    /// Base amount of change to apply wrt ball position, per step,
+    /// used to keep the ball moving, once there is no more data.
     mov: (f32, f32),
     /// The time stamp for which position was generated in the last call
     lastgentime: usize,
-    /// unit step size
-    stepsize: f32,
-    /// % of steps done wrt current interpolation block in 0.0-1.0 form
-    stepdone: f32,
 }
 
 impl VirtBall {
@@ -42,32 +49,72 @@ impl VirtBall {
             vin: 0,
             ltime: 0,
             lpos: (0.0, 0.0),
+            ppos: (0.0, 0.0),
+            stime: 0,
+            spos: (0.0, 0.0),
+            interpolator: Interpolator::Linear,
             cpos: (0.0, 0.0),
             mov: (0.0, 0.0),
             lastgentime: 0,
-            stepsize: 0.0,
-            stepdone: 0.0,
         }
     }
 
+    /// Set the interpolator to use wrt the ball movement between actions
+    pub fn set_interpolator(&mut self, interpolator: Interpolator) {
+        self.interpolator = interpolator;
+    }
+
     fn next_cpos(&mut self) {
         self.cpos = (self.cpos.0 + self.mov.0, self.cpos.1 + self.mov.1);
     }
 
-    fn extract_nextdata(&mut self) -> bool {
-        let sdata = &self.vdata[self.vin];
-        self.vin += 1;
+    /// Parse the record at the given index, into (time, (x, y))
+    fn parse_data(&self, index: usize) -> Option<(usize, (f32, f32))> {
+        let sdata = &self.vdata[index];
         if sdata.trim().len() == 0 {
-            return false;
+            return None;
         }
         let sdata = sdata.split(',').collect::<Vec<&str>>();
-        self.ltime = sdata[0].parse().unwrap();
+        let time = sdata[0].parse().unwrap();
         let fx = sdata[1].parse().unwrap();
         let fy = sdata[2].parse().unwrap();
-        self.lpos = (fx, fy);
+        return Some((time, (fx, fy)));
+    }
+
+    fn extract_nextdata(&mut self) -> bool {
+        let data = self.parse_data(self.vin);
+        self.vin += 1;
+        if data.is_none() {
+            return false;
+        }
+        let (ltime, lpos) = data.unwrap();
+        self.ltime = ltime;
+        self.lpos = lpos;
         return true;
     }
 
+    /// The position wrt the action/ball movement change after the current segment,
+    /// if there is one, else one extended along the current segment.
+    fn peek_nextpos(&self) -> (f32, f32) {
+        if self.vin < self.vdata.len() {
+            if let Some((_time, pos)) = self.parse_data(self.vin) {
+                return pos;
+            }
+        }
+        return (2.0*self.lpos.0 - self.spos.0, 2.0*self.lpos.1 - self.spos.1);
+    }
+
+    /// The position at the given time, within the current segment
+    fn segment_pos(&self, ctime: usize) -> (f32, f32) {
+        let ncycles = (self.ltime - self.stime) as f32;
+        let t = (ctime - self.stime) as f32/ncycles;
+        match self.interpolator {
+            Interpolator::Linear => interpolate::linear(self.spos, self.lpos, t),
+            Interpolator::CatmullRom => interpolate::catmull_rom(self.ppos, self.spos, self.lpos, self.peek_nextpos(), t),
+            Interpolator::Physics => interpolate::decay_over_cycles(self.spos, self.lpos, rcss::BALL_DECAY, ncycles, t),
+        }
+    }
+
     ///
     /// Calculate the interpolated position wrt each requested time.
     /// If the last time is repeated again, the same position is sent.
@@ -80,7 +127,11 @@ impl VirtBall {
     /// done when ever the ball (or rather playback) has just gone past a
     /// known game action time, wrt the next segment.
     ///
-    /// MAYBE: Add support for non linear interpolated movement later.
+    /// The path within a segment depends on the interpolator set
+    /// * Linear: a straight line at constant speed.
+    /// * CatmullRom: a spline through the previous and next action positions.
+    /// * Physics: the ball slows down as per rcss ball decay, as if kicked
+    ///   at the start of the segment.
     ///
     pub fn next_record(&mut self, ctime: usize) -> (f32, f32) {
         if ctime == self.lastgentime {
@@ -91,9 +142,11 @@ impl VirtBall {
             if self.vin >= self.vdata.len() {
                 break;
             }
+            let ppos = self.lpos;
             if !self.extract_nextdata() {
                 break;
             }
+            self.ppos = ppos;
             let dt = self.ltime as isize - ctime as isize;
             if dt < 0 {
                 continue;
@@ -101,13 +154,17 @@ impl VirtBall {
                 self.cpos = self.lpos;
                 return self.lpos;
             }
-            self.stepsize = 1.0/dt as f32;
-            self.stepdone = 0.0;
+            self.stime = ctime - 1;
+            self.spos = self.cpos;
             let dx = (self.lpos.0 - self.cpos.0)/(dt as f32 +1.0);
             let dy = (self.lpos.1 - self.cpos.1)/(dt as f32 +1.0);
             self.mov = (dx,dy);
         }
-        self.next_cpos();
+        if (ctime > self.ltime) || (ctime <= self.stime) {
+            self.next_cpos();
+        } else {
+            self.cpos = self.segment_pos(ctime);
+        }
         self.cpos
     }
 
@@ -3,82 +3,100 @@
 //! HanishKVC, 2022
 //!
 
-use std::collections::HashMap;
-
 use sdl2::pixels::Color;
 use sdl2::ttf::Font;
 
 use loggerk::{ldebug, log_d};
 
-use crate::entities::{ENTITY_WIDTH, ENTITY_HEIGHT};
 use crate::entities::gentity::{GEntity, GEDrawPrimitive};
+use crate::entities::interpolate::Interpolator;
 use crate::proc::actions::{ActionsInfo, ActionData, AIAction};
+use crate::proc::heatmaps::{Heatmaps, HeatmapTarget};
+use crate::proc::pitchcontrol::PCPlayer;
 use crate::sdlx::{SdlX, self, COLOR_INVISIBLE};
 use crate::playdata::{PlayerCodedData, self, rcss};
 
 
 
+/// Colors wrt the body direction tick, neck direction line and view cone
+const COLOR_BODY: Color = Color::BLACK;
+const COLOR_NECK: Color = Color::WHITE;
+const COLOR_VIEWCONE: Color = Color::RGBA(255, 255, 255, 40);
+
 #[derive(Debug)]
 pub struct Team<'a> {
     name: String,
     color: Color,
     players: Vec<GEntity<'a>>,
-    cards: HashMap<String, Vec<usize>>,
+    /// The latest stamina (0.0-1.0) of each player, if known
+    stamina: Vec<Option<f32>>,
     bshowstamina: bool,
     bshowactions: bool,
     bshowcards: bool,
     bshowotheractions: bool,
+    bshowbody: bool,
+    bshowneck: bool,
+    bshowviewcone: bool,
 }
 
 impl<'a> Team<'a> {
 
-    pub fn new(name: &str, color: Color, nplayers: i32, font: &'a Font) -> Team<'a> {
+    pub fn new(name: &str, color: Color, nplayers: i32, width_height: (u32, u32), font: Option<&'a Font>) -> Team<'a> {
         let mut team = Team {
             name: name.to_string(),
             color: color,
             players: Vec::new(),
-            cards: HashMap::new(),
+            stamina: vec![None; nplayers as usize],
             bshowstamina: true,
             bshowactions: true,
             bshowcards: true,
             bshowotheractions: false,
+            bshowbody: false,
+            bshowneck: false,
+            bshowviewcone: false,
         };
         let (prgw, prgh) = sdlx::get_prg_resolution();
         let bx = (rand::random::<u32>() % prgw) as f32;
         for i in 0..nplayers {
             let fx = (rand::random::<u32>() % (prgw/4)) as f32;
             let fy = (rand::random::<u32>() % prgh) as f32;
-            team.players.push(GEntity::new(i.to_string().as_str(), (bx+fx, fy), (ENTITY_WIDTH, ENTITY_HEIGHT), team.color, font));
+            team.players.push(GEntity::new(i.to_string().as_str(), (bx+fx, fy), width_height, team.color, font));
         }
-        team.cards.insert(playdata::Card::Red.to_string(), Vec::new());
-        team.cards.insert(playdata::Card::Yellow.to_string(), Vec::new());
         ldebug!(&format!("INFO:PGND:Team:Created:{}:{:#?}\n", team.name, team));
         team
     }
 
-    pub fn update(&mut self, timecounter: usize, playersdata: Vec<PlayerCodedData>, babsolute: bool, inframes: f32, actionsinfo: &mut ActionsInfo) {
+    pub fn update(&mut self, timecounter: usize, playersdata: Vec<PlayerCodedData>, babsolute: bool, inframes: f32, actionsinfo: &mut ActionsInfo, heatmaps: &mut Heatmaps) {
         let side = self.name.chars().nth(0).unwrap();
+        let dircolors = self.dir_colors();
         for player in playersdata {
             ldebug!(&format!("DBUG:PPGND:Team:{}:{:?}", self.name, player));
             let pi = player.0 as usize;
             let mut px = 0.0;
             let mut py = 0.0;
             let mut pact = AIAction::None;
+            let mut pvel = None;
             for pd in player.1 {
                 match pd {
                     playdata::PlayerData::Pos(fx, fy) => {
                         px = fx;
                         py = fy;
+                        heatmaps.add(timecounter, HeatmapTarget::Player(side, pi), (fx, fy));
                         // Position
                         if babsolute {
                             self.players[pi].pos_set_abs(fx, fy);
                         } else {
                             self.players[pi].move_to_in_frames((fx, fy), inframes);
                         }
+                        self.players[pi].trail_add(timecounter, (fx, fy));
+                    },
+                    playdata::PlayerData::Vel(vx, vy) => {
+                        pvel = Some((vx, vy));
                     },
                     playdata::PlayerData::Stamina(fstamina) => {
                         // Stamina
                         //self.players[ppos.0 as usize].set_fcolor(1.0-fstamina, 1.0);
+                        self.stamina[pi] = Some(fstamina);
                         let istamina = (fstamina * 100.0).round() as i32;
                         let mut stamina_color = match istamina {
                             0..=30 => Color::RED,
@@ -93,15 +111,19 @@ impl<'a> Team<'a> {
                         self.players[pi].set_ll_color(stamina_color);
                         self.players[pi].set_rl_color(stamina_color);
                     },
+                    playdata::PlayerData::Body(angle) => {
+                        self.players[pi].set_body(angle, dircolors.0);
+                    },
+                    playdata::PlayerData::Neck(angle) => {
+                        self.players[pi].set_neck(angle, dircolors.1);
+                    },
+                    playdata::PlayerData::ViewWidth(width) => {
+                        self.players[pi].set_viewcone(width, dircolors.2);
+                    },
                     playdata::PlayerData::Card(card) => {
                         // Cards
-                        let penalised = self.cards.get_mut(&card.to_string());
-                        if penalised.is_some() {
-                            let penalised = penalised.unwrap();
-                            if !penalised.contains(&pi) {
-                                penalised.push(pi);
-                                actionsinfo.handle_card(side, pi, card.clone())
-                            }
+                        if card != playdata::Card::None {
+                            actionsinfo.handle_card(timecounter, side, pi, card.clone());
                         }
                         let mut card_color = sdlx::COLOR_INVISIBLE;
                         if let playdata::Card::Red = card {
@@ -163,16 +185,50 @@ impl<'a> Team<'a> {
                     }
                 }
             }
+            // Velocity wrt the new position, used by any subsequent interpolated movement
+            self.players[pi].set_vel(pvel, rcss::PLAYER_DECAY);
             actionsinfo.handle_action(ActionData::new(timecounter, side, pi, (px,py), pact));
         }
     }
 
+    /// Set the positions the players will be moved to after the current movement,
+    /// as got from the next record.
+    pub fn set_moves_next(&mut self, playersdata: &Vec<PlayerCodedData>) {
+        for player in playersdata {
+            let pi = player.0 as usize;
+            for pd in &player.1 {
+                if let playdata::PlayerData::Pos(fx, fy) = pd {
+                    self.players[pi].set_move_next(Some((*fx, *fy)));
+                }
+            }
+        }
+    }
+
     pub fn next_frame(&mut self) {
         for i in 0..self.players.len() {
             self.players[i].next_frame();
         }
     }
 
+    pub fn move_finish(&mut self) {
+        for i in 0..self.players.len() {
+            self.players[i].move_finish();
+        }
+    }
+
+    pub fn set_interpolator(&mut self, interpolator: Interpolator) {
+        for i in 0..self.players.len() {
+            self.players[i].set_interpolator(interpolator);
+        }
+    }
+
+    /// Set up the trails behind the players, refer GEntity::trail_set
+    pub fn trails_set(&mut self, maxpoints: usize, color: Color) {
+        for i in 0..self.players.len() {
+            self.players[i].trail_set(maxpoints, color);
+        }
+    }
+
     pub fn draw(&mut self, sx: &mut SdlX) {
         for i in 0..self.players.len() {
             self.players[i].draw(sx);
@@ -183,6 +239,53 @@ impl<'a> Team<'a> {
 
 impl<'a> Team<'a> {
 
+    /// The current position of the specified player, in normal space
+    pub fn player_pos(&self, playerid: usize) -> (f32, f32) {
+        return self.players[playerid].pos();
+    }
+
+    /// The latest stamina (0.0-1.0) of the specified player, if known
+    pub fn player_stamina(&self, playerid: usize) -> Option<f32> {
+        return self.stamina[playerid];
+    }
+
+    /// Add the side, position and velocity of the players, wrt pitch control.
+    /// The current positions are used, so that in the interpolated movements mode,
+    /// the pitch control moves along with the players shown.
+    pub fn pcplayers(&self, pcplayers: &mut Vec<PCPlayer>) {
+        let side = self.name.chars().nth(0).unwrap();
+        for player in &self.players {
+            pcplayers.push((side, player.pos(), player.vel()));
+        }
+    }
+
+    /// The positions of the players, either the current ones or else the ones
+    /// set/moved to last (refer GEntity::pos_latest), in normal space.
+    pub fn positions(&self, blatest: bool) -> Vec<(f32, f32)> {
+        let mut vpos = Vec::new();
+        for player in &self.players {
+            if blatest {
+                vpos.push(player.pos_latest());
+            } else {
+                vpos.push(player.pos());
+            }
+        }
+        return vpos;
+    }
+
+    /// Highlight the specified player with a ring, for the specified number of frames
+    pub fn player_highlight(&mut self, playerid: usize, color: Color, nframes: isize) {
+        self.players[playerid].gextras_add(GEDrawPrimitive::NSArc{ remfc: nframes, radratio: 1.8, arcangles: (0, 359), color });
+    }
+
+    pub fn color(&self) -> Color {
+        return self.color;
+    }
+
+    pub fn nplayers(&self) -> usize {
+        self.players.len()
+    }
+
     pub fn adjust_players(&mut self, colorsel: u8) {
         for i in 0..self.players.len() {
             self.players[i].colorsel = colorsel;
@@ -204,4 +307,40 @@ impl<'a> Team<'a> {
         self.bshowcards
     }
 
+    /// The colors wrt the body direction tick, neck direction line and view cone,
+    /// as per whether they are to be shown or not.
+    fn dir_colors(&self) -> (Color, Color, Color) {
+        let body = if self.bshowbody { COLOR_BODY } else { COLOR_INVISIBLE };
+        let neck = if self.bshowneck { COLOR_NECK } else { COLOR_INVISIBLE };
+        let view = if self.bshowviewcone { COLOR_VIEWCONE } else { COLOR_INVISIBLE };
+        return (body, neck, view);
+    }
+
+    /// Apply the body/neck/viewcone colors to the players immidiately,
+    /// rather than waiting for the next update.
+    fn dir_colors_apply(&mut self) {
+        let (body, neck, view) = self.dir_colors();
+        for i in 0..self.players.len() {
+            self.players[i].set_dir_colors(body, neck, view);
+        }
+    }
+
+    pub fn toggle_bshowbody(&mut self) -> bool {
+        self.bshowbody = !self.bshowbody;
+        self.dir_colors_apply();
+        self.bshowbody
+    }
+
+    pub fn toggle_bshowneck(&mut self) -> bool {
+        self.bshowneck = !self.bshowneck;
+        self.dir_colors_apply();
+        self.bshowneck
+    }
+
+    pub fn toggle_bshowviewcone(&mut self) -> bool {
+        self.bshowviewcone = !self.bshowviewcone;
+        self.dir_colors_apply();
+        self.bshowviewcone
+    }
+
 }
@@ -0,0 +1,103 @@
+//!
+//! A timeline strip wrt the playback
+//! HanishKVC, 2022
+//!
+
+use sdl2::pixels::Color;
+use sdl2::render::BlendMode;
+
+use crate::sdlx::{SdlX, XRect};
+
+const TIMELINE_COLOR_BG: Color = Color::RGBA(40, 40, 40, 160);
+const TIMELINE_COLOR_DONE: Color = Color::RGBA(200, 200, 200, 120);
+const TIMELINE_COLOR_CURSOR: Color = Color::BLACK;
+const TIMELINE_COLOR_TEXT: Color = Color::WHITE;
+
+
+#[derive(Debug)]
+/// Show a timeline strip on the screen, which gives the current position
+/// wrt the length of the match, along with colored markers (ticks) wrt
+/// events of interest.
+///
+/// It also helps map a position on the strip back to time, to allow seeking.
+pub struct Timeline {
+    /// The strip on the screen, in normalised 0.0-1.0 space.
+    nrect: XRect,
+    /// The markers as (time, color, height relative to strip height)
+    markers: Vec<(usize, Color, f32)>,
+    /// The time corresponding to the end of the strip
+    tmax: usize,
+}
+
+impl Timeline {
+
+    pub fn new(nrect: XRect) -> Timeline {
+        Timeline {
+            nrect: nrect,
+            markers: Vec::new(),
+            tmax: 1,
+        }
+    }
+
+    /// Set the time corresponding to the end of the strip
+    pub fn set_tmax(&mut self, tmax: usize) {
+        self.tmax = tmax.max(1);
+    }
+
+    pub fn markers_clear(&mut self) {
+        self.markers.clear();
+    }
+
+    /// Add a marker wrt the given time. If there is already a marker at the
+    /// same time, the taller (ie more significant) of the two is kept.
+    /// * nheight: height of the marker relative to the strip height
+    pub fn marker_add(&mut self, time: usize, color: Color, nheight: f32) {
+        if let Some(marker) = self.markers.iter_mut().find(|m| m.0 == time) {
+            if marker.2 < nheight {
+                *marker = (time, color, nheight);
+            }
+            return;
+        }
+        self.markers.push((time, color, nheight));
+    }
+
+    /// Map time to x position along the strip, in normal space
+    fn time2nx(&self, time: usize) -> f32 {
+        let ((nx1,_ny1),(nx2,_ny2)) = self.nrect;
+        let ratio = (time as f32/self.tmax as f32).min(1.0);
+        return nx1 + (nx2-nx1)*ratio;
+    }
+
+    /// Check if the given position (in normal space) is on the strip
+    pub fn contains(&self, nx: f32, ny: f32) -> bool {
+        let ((nx1,ny1),(nx2,ny2)) = self.nrect;
+        return (nx >= nx1) && (nx <= nx2) && (ny >= ny1) && (ny <= ny2);
+    }
+
+    /// Map x position along the strip (in normal space) to time
+    pub fn nx2time(&self, nx: f32) -> usize {
+        let ((nx1,_ny1),(nx2,_ny2)) = self.nrect;
+        let ratio = ((nx - nx1)/(nx2-nx1)).clamp(0.0, 1.0);
+        return (ratio * self.tmax as f32).round() as usize;
+    }
+
+    /// Draw the timeline, with the current position set to the given time.
+    pub fn draw(&self, sx: &mut SdlX, ctime: usize) {
+        let ((nx1,ny1),(nx2,ny2)) = self.nrect;
+        let nh = ny2-ny1;
+        sx.wc.set_blend_mode(BlendMode::Blend);
+        sx.wc.set_draw_color(TIMELINE_COLOR_BG);
+        sx.nn_fill_rect(nx1, ny1, nx2-nx1, nh);
+        let cnx = self.time2nx(ctime);
+        sx.wc.set_draw_color(TIMELINE_COLOR_DONE);
+        sx.nn_fill_rect(nx1, ny1, cnx-nx1, nh);
+        for marker in &self.markers {
+            let mnx = self.time2nx(marker.0);
+            let mny = ny2 - nh*marker.2;
+            sx.nn_line(mnx, mny, mnx, ny2, marker.1);
+        }
+        sx.nn_thick_line(cnx, ny1-nh*0.2, cnx, ny2, 0.003, TIMELINE_COLOR_CURSOR);
+        sx.n_string(nx2-0.1, ny1-nh*1.2, &format!("{}/{}", ctime, self.tmax), TIMELINE_COLOR_TEXT);
+    }
+
+}
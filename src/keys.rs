@@ -4,162 +4,492 @@
 //!
 
 use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::MouseButton;
 
 use loggerk::{ldebug, log_d};
 
-use crate::{sdlx::SdlX, proc::actions};
+use crate::{sdlx::{SdlX, XSpaces}, proc::actions};
+use crate::proc::events::EventType;
+use crate::config::Config;
 
+#[derive(Debug, Clone)]
 pub enum ProgramEvent {
     None,
     Pause,
+    /// Step forward by one cycle, while paused
+    StepForward,
+    /// Step backward by one cycle, while paused
+    StepBackward,
+    /// Switch between forward and reverse playback
+    ToggleReverse,
+    /// Cycle through the reverse playback speeds
+    ReverseSpeedCycle,
+    /// Mark A / B / Clear wrt A-B loop
+    ABLoopMark,
     BackgroundColorChange,
+    /// The program window was resized
+    WindowResized,
+    /// Switch between fullscreen and windowed mode
+    ToggleFullscreen,
     ToggleShowHelp,
     ToggleShowActions,
     ToggleShowBall,
     ToggleShowStamina,
     ToggleShowCards,
+    /// Show/hide the player body direction
+    ToggleShowBody,
+    /// Show/hide the player neck direction
+    ToggleShowNeck,
+    /// Show/hide the player view cone
+    ToggleShowViewCone,
+    /// Show/hide the trails behind the ball and players
+    ToggleShowTrails,
+    /// Cycle the heatmap shown, between none, ball, each team and selected player
+    HeatmapCycle,
+    /// Cycle the time window wrt the heatmap
+    HeatmapWindowCycle,
+    /// Cycle the pitch control overlay, between none, voronoi and time to reach
+    PitchControlCycle,
+    /// Show/hide the offside lines
+    ToggleShowOffside,
+    /// Show/hide the team shapes (convex hull and centroid)
+    ToggleShowShapes,
+    /// Show/hide the event log panel
+    ToggleShowEventLog,
+    /// Show/hide the action location markers
+    ToggleShowMarkers,
+    /// Cycle the time window over which the action markers fade out
+    MarkersWindowCycle,
+    /// Cycle the action type whose markers are shown
+    MarkersActionCycle,
+    /// Limit the action markers to the selected player or not
+    MarkersPlayerToggle,
     SeekBackward,
     SeekForward,
+    /// Select the type of event to seek to
+    SeekEventSelect(EventType),
+    /// Cycle the player selected wrt player actions events, by the given delta
+    SeekEventPlayer(isize),
+    /// Seek to the next (+ve) or previous (-ve) event of the selected type
+    SeekEvent(isize),
     AdjustFPS(f32),
+    /// Adjust the playback speed multiplier by the given ratio
+    AdjustSpeed(f32),
+    /// Switch between interpolated and discrete movements
+    ToggleInterpolate,
+    /// Cycle through the interpolators wrt interpolated movements
+    InterpolatorCycle,
     SendRecordCoded(isize),
     DumpPGEntities,
     DumpAIScoresSummary(char),
     DumpAIDistancesSummary(char),
     DumpIncCardScore,
+    /// Cycle the passing network shown, between none, left team and right team
+    DumpPassNetworkCycle,
+    /// Export the team shape metrics over time
+    DumpTeamShapes,
+    /// Mouse left button pressed at the given position in normal space
+    MouseDown(f32, f32),
+    /// Mouse moved with left button pressed, to the given position in normal space
+    MouseDrag(f32, f32),
+    /// Mouse left button released at the given position in normal space
+    MouseUp(f32, f32),
+    /// Mouse wheel scrolled by the given amount, +ve is away from the user
+    MouseWheel(i32),
+    /// Cycle the camera follow mode, between none, ball and selected player
+    CameraFollowCycle,
+    /// Reset the camera zoom and pan
+    CameraReset,
+    /// Open the command palette
+    CommandPalette,
+    /// Add the given text to the command palette search
+    PaletteInput(String),
+    /// Remove the last char from the command palette search
+    PaletteBackspace,
+    /// Move the command palette selection by the given delta
+    PaletteMove(isize),
+    /// Run the selected command palette entry
+    PaletteRun,
+    /// Close the command palette
+    PaletteClose,
     Quit,
     NeedMore,
 }
 
-fn handle_s_cmds(keycode: Keycode) -> ProgramEvent {
-    match keycode {
-        Keycode::A => {
-            return ProgramEvent::ToggleShowActions;
-        },
-        Keycode::B => {
-            return ProgramEvent::ToggleShowBall;
-        },
-        Keycode::S => {
-            return ProgramEvent::ToggleShowStamina;
-        },
-        Keycode::C => {
-            return ProgramEvent::ToggleShowCards;
-        },
-        Keycode::H => {
-            return ProgramEvent::ToggleShowHelp;
-        },
-        _ => (),
+/// The prefix (mode) key, wrt the command palette
+pub const PALETTE_PREFIX: &str = ":";
+
+#[derive(Debug, Clone)]
+/// A key sequence mapped to a program event.
+///
+/// The sequence is made up of a optional prefix (mode) key, followed by a key,
+/// with or without shift.
+pub struct KeyBinding {
+    /// The command name, used wrt config and command palette
+    pub name: &'static str,
+    /// The description, used wrt help and command palette
+    pub desc: &'static str,
+    pub prefix: Option<Keycode>,
+    pub key: Keycode,
+    pub shift: bool,
+    pub pev: ProgramEvent,
+}
+
+impl KeyBinding {
+
+    fn new(name: &'static str, desc: &'static str, seq: &str, pev: ProgramEvent) -> KeyBinding {
+        let (prefix, key, shift) = parse_keyseq(seq).unwrap();
+        KeyBinding { name, desc, prefix, key, shift, pev }
+    }
+
+    /// The key sequence, like s t or Shift+R, in the same form as used in config
+    pub fn keyseq(&self) -> String {
+        let skey = if self.shift { format!("Shift+{}", self.key.name()) } else { self.key.name() };
+        return match self.prefix {
+            Some(prefix) => format!("{} {}", prefix.name(), skey),
+            None => skey,
+        };
+    }
+
+    /// The key sequence in a short form, like st or R, as used in help
+    pub fn keyseq_short(&self) -> String {
+        let kname = |key: Keycode, shift: bool| {
+            let name = key.name();
+            if name.chars().count() == 1 {
+                if shift { name.to_uppercase() } else { name.to_lowercase() }
+            } else if shift {
+                format!("Shift+{}", name)
+            } else {
+                name
+            }
+        };
+        return match self.prefix {
+            Some(prefix) => format!("{}{}", kname(prefix, false), kname(self.key, self.shift)),
+            None => kname(self.key, self.shift),
+        };
     }
-    return ProgramEvent::None;
+
 }
 
-fn handle_c_cmds(keycode: Keycode) -> ProgramEvent {
-    match keycode {
-        Keycode::Num0 => {
-            return ProgramEvent::SendRecordCoded(0);
-        },
-        Keycode::Num1 => {
-            return ProgramEvent::SendRecordCoded(1);
-        },
-        _ => (),
+/// Parse a key sequence like [prefixkey] [Shift+]key, using sdl key names
+pub fn parse_keyseq(seq: &str) -> Result<(Option<Keycode>, Keycode, bool), String> {
+    let parse_key = |name: &str| Keycode::from_name(name).ok_or(format!("unknown key name [{}] in [{}]", name, seq.trim()));
+    let parts: Vec<&str> = seq.split_whitespace().collect();
+    let (prefix, skey) = match parts.len() {
+        1 => (None, parts[0]),
+        2 => (Some(parse_key(parts[0])?), parts[1]),
+        _ => return Err(format!("expected [prefixkey] [Shift+]key, got [{}]", seq.trim())),
+    };
+    let (shift, skey) = match skey.strip_prefix("Shift+") {
+        Some(skey) => (true, skey),
+        None => (false, skey),
+    };
+    return Ok((prefix, parse_key(skey)?, shift));
+}
+
+#[derive(Debug, Clone)]
+/// The key bindings of the program, which can be changed using the config.
+/// Any key which starts a multi key sequence, is treated as a prefix (mode) key.
+pub struct KeyMap {
+    bindings: Vec<KeyBinding>,
+}
+
+impl Default for KeyMap {
+
+    fn default() -> Self {
+        use ProgramEvent as PE;
+        let kb = KeyBinding::new;
+        let bindings = vec![
+            kb("seek_back", "seek back", "Left", PE::SeekBackward),
+            kb("seek_forward", "seek forward", "Right", PE::SeekForward),
+            kb("seek_prev_event", "seek prev event", "[", PE::SeekEvent(-1)),
+            kb("seek_prev_event", "seek prev event", "E [", PE::SeekEvent(-1)),
+            kb("seek_next_event", "seek next event", "]", PE::SeekEvent(1)),
+            kb("seek_next_event", "seek next event", "E ]", PE::SeekEvent(1)),
+            kb("event_goal", "event type goal", "E G", PE::SeekEventSelect(EventType::Goal)),
+            kb("event_card", "event type card", "E C", PE::SeekEventSelect(EventType::Card)),
+            kb("event_setpiece", "event type set piece", "E S", PE::SeekEventSelect(EventType::SetPiece)),
+            kb("event_playmode", "event type playmode", "E M", PE::SeekEventSelect(EventType::PlayMode)),
+            kb("event_kick", "event type kick", "E K", PE::SeekEventSelect(EventType::Kick)),
+            kb("event_playeractions", "event type player actions", "E A", PE::SeekEventSelect(EventType::PlayerAction)),
+            kb("event_next_player", "event next player", "E N", PE::SeekEventPlayer(1)),
+            kb("event_prev_player", "event prev player", "E Shift+N", PE::SeekEventPlayer(-1)),
+            kb("fps_down", "decrease fps", "F", PE::AdjustFPS(0.80)),
+            kb("fps_up", "increase fps", "Shift+F", PE::AdjustFPS(1.20)),
+            kb("toggle_interpolate", "interpolated/discrete moves", "I", PE::ToggleInterpolate),
+            kb("interpolator_cycle", "cycle interpolator", "Shift+I", PE::InterpolatorCycle),
+            kb("speed_down", "slower playback", "-", PE::AdjustSpeed(0.5)),
+            kb("speed_up", "faster playback", "=", PE::AdjustSpeed(2.0)),
+            kb("speed_up", "faster playback", "+", PE::AdjustSpeed(2.0)),
+            kb("pause", "pause playback", "P", PE::Pause),
+            kb("step_back", "step back a cycle", ",", PE::StepBackward),
+            kb("step_forward", "step forward a cycle", ".", PE::StepForward),
+            kb("toggle_reverse", "reverse playback", "R", PE::ToggleReverse),
+            kb("reverse_speed_cycle", "cycle reverse speed", "Shift+R", PE::ReverseSpeedCycle),
+            kb("abloop_mark", "mark A-B loop", "L", PE::ABLoopMark),
+            kb("bgcolor_change", "change background color", "B", PE::BackgroundColorChange),
+            kb("toggle_stamina", "show/hide stamina", "S S", PE::ToggleShowStamina),
+            kb("toggle_actions", "show/hide actions", "S A", PE::ToggleShowActions),
+            kb("toggle_ball", "show/hide ball", "S B", PE::ToggleShowBall),
+            kb("toggle_cards", "show/hide cards", "S C", PE::ToggleShowCards),
+            kb("toggle_body", "show/hide body dir", "S O", PE::ToggleShowBody),
+            kb("toggle_neck", "show/hide neck dir", "S N", PE::ToggleShowNeck),
+            kb("toggle_viewcone", "show/hide view cone", "S V", PE::ToggleShowViewCone),
+            kb("toggle_trails", "show/hide trails", "S T", PE::ToggleShowTrails),
+            kb("heatmap_cycle", "cycle heatmap", "S M", PE::HeatmapCycle),
+            kb("heatmap_window_cycle", "cycle heatmap time window", "S W", PE::HeatmapWindowCycle),
+            kb("pitchcontrol_cycle", "cycle pitch control mode", "S P", PE::PitchControlCycle),
+            kb("toggle_offside", "show/hide offside lines", "S F", PE::ToggleShowOffside),
+            kb("toggle_shapes", "show/hide team shapes", "S G", PE::ToggleShowShapes),
+            kb("toggle_eventlog", "show/hide event log", "S L", PE::ToggleShowEventLog),
+            kb("toggle_markers", "show/hide action markers", "S K", PE::ToggleShowMarkers),
+            kb("markers_window_cycle", "cycle action markers window", "S Shift+K", PE::MarkersWindowCycle),
+            kb("markers_action_cycle", "cycle action markers type", "S J", PE::MarkersActionCycle),
+            kb("markers_player_toggle", "action markers of event player", "S Shift+J", PE::MarkersPlayerToggle),
+            kb("rclive_kickoff", "RCLive kick-off", "C 1", PE::SendRecordCoded(1)),
+            kb("rclive_init", "RCLive init handshake", "C 0", PE::SendRecordCoded(0)),
+            kb("dump_entities", "dump entities", "D E", PE::DumpPGEntities),
+            kb("perfbars_team", "perf bars, team relative", "D A", PE::DumpAIScoresSummary(actions::SUMMARY_RELATIVE_TEAM)),
+            kb("perfbars_all", "perf bars, all relative", "D Shift+A", PE::DumpAIScoresSummary(actions::SUMMARY_RELATIVE_ALL)),
+            kb("distbars_team", "distance bars, team relative", "D D", PE::DumpAIDistancesSummary(actions::SUMMARY_RELATIVE_TEAM)),
+            kb("distbars_all", "distance bars, all relative", "D Shift+D", PE::DumpAIDistancesSummary(actions::SUMMARY_RELATIVE_ALL)),
+            kb("toggle_cardscore", "include card in scores", "D C", PE::DumpIncCardScore),
+            kb("passnetwork_cycle", "cycle pass network", "D P", PE::DumpPassNetworkCycle),
+            kb("export_shapes", "export team shapes csv", "D S", PE::DumpTeamShapes),
+            kb("camera_follow_cycle", "camera follow cycle", "Z", PE::CameraFollowCycle),
+            kb("camera_reset", "camera reset", "Shift+Z", PE::CameraReset),
+            kb("toggle_fullscreen", "fullscreen/windowed", "F11", PE::ToggleFullscreen),
+            kb("command_palette", "command palette", "/", PE::CommandPalette),
+            kb("toggle_help", "hide/unhide help", "H", PE::ToggleShowHelp),
+            kb("toggle_help", "hide/unhide help", "S H", PE::ToggleShowHelp),
+        ];
+        KeyMap { bindings }
+    }
+
+}
+
+impl KeyMap {
+
+    /// Replace the key sequences of the given command, with the given | seperated
+    /// key sequences, like  S T | F2
+    pub fn rebind(&mut self, name: &str, seqs: &str) -> Result<(), String> {
+        let template = match self.bindings.iter().find(|kb| kb.name == name) {
+            Some(kb) => kb.clone(),
+            None => return Err(format!("unknown command [{}]", name)),
+        };
+        let mut newbindings = Vec::new();
+        for seq in seqs.split('|') {
+            let (prefix, key, shift) = parse_keyseq(seq)?;
+            newbindings.push(KeyBinding { prefix, key, shift, ..template.clone() });
+        }
+        let at = self.bindings.iter().position(|kb| kb.name == name).unwrap();
+        self.bindings.retain(|kb| kb.name != name);
+        for (i, kb) in newbindings.into_iter().enumerate() {
+            self.bindings.insert(at+i, kb);
+        }
+        return Ok(());
+    }
+
+    /// Check that no key sequence is bound to more than one command, and that
+    /// the prefix keys are not bound as commands by themselves.
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, kb) in self.bindings.iter().enumerate() {
+            for other in self.bindings[i+1..].iter() {
+                if (kb.prefix == other.prefix) && (kb.key == other.key) && (kb.shift == other.shift) && (kb.name != other.name) {
+                    return Err(format!("key [{}] bound to both {} and {}", kb.keyseq(), kb.name, other.name));
+                }
+            }
+            if kb.prefix.is_none() && self.is_prefix(kb.key) {
+                return Err(format!("key [{}] of {} is also used as a prefix key", kb.keyseq(), kb.name));
+            }
+        }
+        return Ok(());
+    }
+
+    /// Check if the given key starts a multi key sequence
+    pub fn is_prefix(&self, key: Keycode) -> bool {
+        return self.bindings.iter().any(|kb| kb.prefix == Some(key));
+    }
+
+    /// Check if the given prefix key leads to the event navigation commands,
+    /// ie seeking to and selecting the type of events.
+    pub fn is_event_prefix(&self, prefix: Keycode) -> bool {
+        return self.bindings.iter().filter(|kb| kb.prefix == Some(prefix)).any(|kb| {
+            matches!(kb.pev, ProgramEvent::SeekEvent(_) | ProgramEvent::SeekEventSelect(_))
+        });
+    }
+
+    /// Find the prefix key with the given name (ignoring case)
+    pub fn prefix_named(&self, name: &str) -> Option<Keycode> {
+        return self.bindings.iter().filter_map(|kb| kb.prefix).find(|prefix| prefix.name().eq_ignore_ascii_case(name));
+    }
+
+    /// Find the program event bound to the given key sequence. If there is no
+    /// binding specific to shift being pressed, the one without shift is used.
+    pub fn lookup(&self, prefix: Option<Keycode>, key: Keycode, shift: bool) -> Option<ProgramEvent> {
+        let find = |shift: bool| self.bindings.iter().find(|kb| (kb.prefix == prefix) && (kb.key == key) && (kb.shift == shift));
+        let kb = find(shift).or_else(|| if shift { find(false) } else { None });
+        return kb.map(|kb| kb.pev.clone());
+    }
+
+    /// The commands, with all the key sequences (short form) of each command
+    /// and its description, in the order of the bindings.
+    pub fn commands(&self) -> Vec<(&'static str, String, &'static str)> {
+        return Self::commands_of(self.bindings.iter().map(|kb| (kb, kb.keyseq_short())));
+    }
+
+    /// Combine the given bindings and their key sequences, into one entry per command
+    fn commands_of<'a>(kbseqs: impl Iterator<Item = (&'a KeyBinding, String)>) -> Vec<(&'static str, String, &'static str)> {
+        let mut cmds: Vec<(&'static str, String, &'static str)> = Vec::new();
+        for (kb, seq) in kbseqs {
+            match cmds.iter_mut().find(|c| c.0 == kb.name) {
+                Some(cmd) => cmd.1 = format!("{}/{}", cmd.1, seq),
+                None => cmds.push((kb.name, seq, kb.desc)),
+            }
+        }
+        return cmds;
+    }
+
+    fn help_line(seqs: &str, desc: &str) -> String {
+        return format!("{:8}{}", format!("{}:", seqs), desc);
+    }
+
+    /// The help lines, one per command, like  st:     show/hide trails
+    pub fn help_lines(&self) -> Vec<String> {
+        return self.commands().iter().map(|(_name, seqs, desc)| Self::help_line(seqs, desc)).collect();
     }
-    return ProgramEvent::None;
+
+    /// The help lines wrt the commands bound under the given prefix key, one per
+    /// command, with only the keys to press after the prefix, like  g:      event type goal
+    pub fn prefix_help_lines(&self, prefix: Keycode) -> Vec<String> {
+        let kbseqs = self.bindings.iter().filter(|kb| kb.prefix == Some(prefix)).map(|kb| (kb, KeyBinding { prefix: None, ..kb.clone() }.keyseq_short()));
+        return Self::commands_of(kbseqs).iter().map(|(_name, seqs, desc)| Self::help_line(seqs, desc)).collect();
+    }
+
+    /// The commands whose name, description or key sequence contains the given
+    /// query (ignoring case), along with the program event of each.
+    pub fn search(&self, query: &str) -> Vec<(String, ProgramEvent)> {
+        let query = query.to_lowercase();
+        let mut found = Vec::new();
+        for (name, seqs, desc) in self.commands() {
+            let line = format!("{:8}{} [{}]", seqs, desc, name);
+            if line.to_lowercase().contains(&query) {
+                let pev = self.bindings.iter().find(|kb| kb.name == name).unwrap().pev.clone();
+                found.push((line, pev));
+            }
+        }
+        return found;
+    }
+
 }
 
-fn handle_d_cmds(keycode: Keycode, keymod: Mod) -> ProgramEvent {
+/// Map keys to the command palette events. The text typed in is got through
+/// the sdl text input events, so that it follows the keyboard layout.
+fn handle_palette_keys(keycode: Keycode) -> ProgramEvent {
     match keycode {
-        Keycode::E => {
-            return ProgramEvent::DumpPGEntities;
+        Keycode::Escape => ProgramEvent::PaletteClose,
+        Keycode::Return | Keycode::KpEnter => ProgramEvent::PaletteRun,
+        Keycode::Backspace => ProgramEvent::PaletteBackspace,
+        Keycode::Up => ProgramEvent::PaletteMove(-1),
+        Keycode::Down => ProgramEvent::PaletteMove(1),
+        _ => ProgramEvent::NeedMore,
+    }
+}
+
+/// Map mouse (left button) events into program events,
+/// with the mouse position converted to normal space.
+fn handle_mouse(n2s: &XSpaces, ev: &sdl2::event::Event) -> Option<ProgramEvent> {
+    use sdl2::event::Event;
+    match *ev {
+        Event::MouseButtonDown { mouse_btn: MouseButton::Left, x, y, .. } => {
+            let (nx, ny) = n2s.o2d((x as f32, y as f32));
+            return Some(ProgramEvent::MouseDown(nx, ny));
         },
-        Keycode::A => {
-            if keymod.contains(Mod::RSHIFTMOD) || keymod.contains(Mod::LSHIFTMOD) {
-                return ProgramEvent::DumpAIScoresSummary(actions::SUMMARY_RELATIVE_ALL);
-            } else {
-                return ProgramEvent::DumpAIScoresSummary(actions::SUMMARY_RELATIVE_TEAM);
+        Event::MouseMotion { mousestate, x, y, .. } => {
+            if mousestate.left() {
+                let (nx, ny) = n2s.o2d((x as f32, y as f32));
+                return Some(ProgramEvent::MouseDrag(nx, ny));
             }
+            return Some(ProgramEvent::NeedMore);
         },
-        Keycode::D => {
-            if keymod.contains(Mod::RSHIFTMOD) || keymod.contains(Mod::LSHIFTMOD) {
-                return ProgramEvent::DumpAIDistancesSummary(actions::SUMMARY_RELATIVE_ALL);
-            } else {
-                return ProgramEvent::DumpAIDistancesSummary(actions::SUMMARY_RELATIVE_TEAM);
-            }
+        Event::MouseButtonUp { mouse_btn: MouseButton::Left, x, y, .. } => {
+            let (nx, ny) = n2s.o2d((x as f32, y as f32));
+            return Some(ProgramEvent::MouseUp(nx, ny));
         },
-        Keycode::C => {
-            return ProgramEvent::DumpIncCardScore;
-        }
-        Keycode::LShift | Keycode::RShift => return ProgramEvent::NeedMore,
-        _ => ldebug!(&format!("DBUG:GPPGND:Keys:DCmds:{}:{}", keycode, keymod)),
+        Event::MouseWheel { y, .. } => {
+            return Some(ProgramEvent::MouseWheel(y));
+        },
+        _ => return None,
+    }
+}
+
+/// Handle window related events
+fn handle_window(ev: &sdl2::event::Event) -> Option<ProgramEvent> {
+    use sdl2::event::{Event, WindowEvent};
+    match ev {
+        Event::Window { win_event: WindowEvent::SizeChanged(_, _), .. } => Some(ProgramEvent::WindowResized),
+        Event::Window { .. } => Some(ProgramEvent::NeedMore),
+        _ => None,
     }
-    return ProgramEvent::None;
 }
 
-pub fn get_programevents(sx: &mut SdlX, skey: &mut String) -> ProgramEvent {
-    for ev in sx.ep.poll_iter() {
+/// Get the program event wrt the next sdl event, if any.
+///
+/// Keys are mapped to the default keys they stand for, as given in the config,
+/// and inturn looked up in the key bindings. If a prefix key is pressed, it is
+/// remembered in skey, till a key not bound wrt that prefix is pressed.
+pub fn get_programevents(sx: &mut SdlX, skey: &mut String, config: &Config) -> ProgramEvent {
+    while let Some(ev) = sx.ep.poll_event() {
         use sdl2::event::Event;
-        if skey.len() > 0 {
-            match ev {
-                Event::Quit { timestamp: _} => return ProgramEvent::Quit,
-                Event::KeyDown { timestamp: _, window_id: _, keycode, scancode: _, keymod, repeat: _ } => {
-                    let mut pev = ProgramEvent::None;
-                    if skey == "s" {
-                        pev = handle_s_cmds(keycode.unwrap());
-                    }
-                    if skey == "c" {
-                        pev = handle_c_cmds(keycode.unwrap());
-                    }
-                    if skey == "d" {
-                        pev = handle_d_cmds(keycode.unwrap(), keymod);
-                    }
-                    if let ProgramEvent::None = pev {
-                        skey.clear();
-                    }
-                    return pev;
-                },
-                _ => return ProgramEvent::None,
-            }
+        if let Some(pev) = handle_window(&ev) {
+            return pev;
+        }
+        if let Some(pev) = handle_mouse(&sx.n2s, &ev) {
+            return pev;
         }
-        skey.clear();
         match ev {
             Event::Quit { timestamp: _ } => return ProgramEvent::Quit,
-            Event::KeyDown { timestamp: _, window_id: _, keycode, scancode: _, keymod, repeat: _ } => {
-                match keycode.unwrap() {
-                    Keycode::B => {
-                        return ProgramEvent::BackgroundColorChange;
-                    }
-                    Keycode::C => {
-                        skey.push('c');
-                        return ProgramEvent::NeedMore;
-                    }
-                    Keycode::P => {
-                        return ProgramEvent::Pause;
-                    }
-                    Keycode::S => {
-                        skey.push('s');
-                        return ProgramEvent::NeedMore;
-                    }
-                    Keycode::Left => {
-                        return ProgramEvent::SeekBackward;
-                    }
-                    Keycode::Right => {
-                        return ProgramEvent::SeekForward;
-                    }
-                    Keycode::F => {
-                        if keymod.contains(Mod::RSHIFTMOD) || keymod.contains(Mod::LSHIFTMOD) {
-                            return ProgramEvent::AdjustFPS(1.20);
-                        } else {
-                            return ProgramEvent::AdjustFPS(0.80);
-                        }
-                    }
-                    Keycode::H => {
-                        return ProgramEvent::ToggleShowHelp;
-                    }
-                    Keycode::D => {
-                        skey.push('d');
-                        return ProgramEvent::NeedMore;
+            Event::TextInput { text, .. } => {
+                if skey == PALETTE_PREFIX {
+                    return ProgramEvent::PaletteInput(text);
+                }
+            },
+            Event::KeyDown { timestamp: _, window_id: _, keycode: Some(keycode), scancode: _, keymod, repeat: _ } => {
+                if skey == PALETTE_PREFIX {
+                    let pev = handle_palette_keys(keycode);
+                    if let ProgramEvent::PaletteClose | ProgramEvent::PaletteRun = pev {
+                        skey.clear();
                     }
-                    _ => {
-
+                    return pev;
+                }
+                let keycode = config.map_key(keycode);
+                if let Keycode::LShift | Keycode::RShift = keycode {
+                    return ProgramEvent::NeedMore;
+                }
+                let shift = keymod.contains(Mod::RSHIFTMOD) || keymod.contains(Mod::LSHIFTMOD);
+                if skey.len() > 0 {
+                    // The prefix mode continues, till a key not bound in it is pressed
+                    let prefix = config.keymap.prefix_named(skey);
+                    let pev = config.keymap.lookup(prefix, keycode, shift);
+                    if pev.is_none() {
+                        ldebug!(&format!("DBUG:GPPGND:Keys:Unbound:{}:{}:{}", skey, keycode, keymod));
+                        skey.clear();
                     }
+                    return pev.unwrap_or(ProgramEvent::None);
+                }
+                if config.keymap.is_prefix(keycode) {
+                    skey.push_str(&keycode.name().to_lowercase());
+                    return ProgramEvent::NeedMore;
+                }
+                match config.keymap.lookup(None, keycode, shift) {
+                    Some(ProgramEvent::CommandPalette) => {
+                        skey.push_str(PALETTE_PREFIX);
+                        // Drop the text of the key which opened the palette
+                        sx.flush_text_input();
+                        return ProgramEvent::CommandPalette;
+                    },
+                    Some(pev) => return pev,
+                    None => (),
                 }
             },
             _ => (),
@@ -167,3 +497,47 @@ pub fn get_programevents(sx: &mut SdlX, skey: &mut String) -> ProgramEvent {
     }
     ProgramEvent::None
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_keyseq_forms() {
+        assert_eq!(parse_keyseq("Left"), Ok((None, Keycode::Left, false)));
+        assert_eq!(parse_keyseq(" E G "), Ok((Some(Keycode::E), Keycode::G, false)));
+        assert_eq!(parse_keyseq("Shift+A"), Ok((None, Keycode::A, true)));
+        assert_eq!(parse_keyseq("D Shift+S"), Ok((Some(Keycode::D), Keycode::S, true)));
+        assert!(parse_keyseq("").is_err());
+        assert!(parse_keyseq("D S X").is_err());
+        assert!(parse_keyseq("NoSuchKey").is_err());
+        assert!(parse_keyseq("NoSuchKey G").is_err());
+    }
+
+    #[test]
+    fn prefix_help_lines_wrt_event_keys() {
+        let keymap = KeyMap::default();
+        let lines = keymap.prefix_help_lines(Keycode::E);
+        assert_eq!(lines[0], KeyMap::help_line("[", "seek prev event"));
+        assert!(lines.contains(&KeyMap::help_line("g", "event type goal")));
+        assert!(lines.contains(&KeyMap::help_line("N", "event prev player")));
+        assert!(!lines.iter().any(|l| l.contains("trails")));
+    }
+
+    #[test]
+    fn event_prefix_wrt_bindings() {
+        let mut keymap = KeyMap::default();
+        assert!(keymap.is_event_prefix(Keycode::E));
+        assert!(!keymap.is_event_prefix(Keycode::S));
+        for name in ["seek_prev_event", "seek_next_event"] {
+            keymap.rebind(name, "Left").unwrap();
+        }
+        for (name, seq) in [("event_goal", "X G"), ("event_card", "X C"), ("event_setpiece", "X S"), ("event_playmode", "X M"), ("event_kick", "X K"), ("event_playeractions", "X A")] {
+            keymap.rebind(name, seq).unwrap();
+        }
+        assert!(keymap.is_event_prefix(Keycode::X));
+        assert!(!keymap.is_event_prefix(Keycode::E));
+    }
+
+}
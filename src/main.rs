@@ -6,108 +6,131 @@
 use std::time;
 
 use proc::actions;
+use proc::events::EventType;
+use sdl2::keyboard::Keycode;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
 use sdl2::ttf::Font;
 
 use loggerk::{log_init, ldebug, log_d};
-use argsclsk::ArgsCmdLineSimpleManager;
 
 mod entities;
 mod sdlx;
 mod playdata;
 use playdata::rcg::Rcg;
 use playdata::random::RandomData;
-use playdata::{PlayData, rclive};
+use playdata::{PlayData, rclive, rcss};
 use playdata::rclive::RCLive;
 use sdlx::SdlX;
 use entities::PGEntities;
+use entities::timeline::Timeline;
+use entities::eventlog::EventLog;
+use entities::palette::Palette;
+use entities::interpolate::Interpolator;
+use playdata::GameState;
+use proc::heatmaps::HeatmapTarget;
 
 mod testlib;
 mod keys;
 mod proc;
+mod config;
+use config::Config;
 
 
 const MTAG: &str = "GPPGND:Main";
 
-struct Cfg {
-    mode: String,
-    src: String,
-    save_interval: usize,
-    fps: f32,
-    fvirtball: String,
-}
-
-impl Cfg {
+/// The default length (in seconds) of the trails behind the ball and players
+pub(crate) const TRAIL_SECONDS: f32 = 3.0;
 
-    ///
-    /// Parse commandline args to configure the program
-    ///
-    /// --mode random
-    /// --mode rclive [--src <the network addr>]
-    /// --mode rcg --src <path/file>
-    ///
-    /// --save_interval <0 or above> # 0 disable saving playback screen
-    ///
-    /// --fps <playback fps>
-    ///
-    /// --virtball <path/virtball.csv>
-    ///
-    fn load() -> Cfg {
-
-        let mut cfg = Cfg {
-            mode: String::from("random"),
-            src: String::new(),
-            save_interval: 0,
-            fps: entities::FRAMES_PER_SEC as f32,
-            fvirtball: String::new(),
-        };
+/// The suffix (extension) of the file into which the team shape metrics over
+/// time are exported
+const TEAMSHAPES_CSV: &str = "teamshapes.csv";
 
-        let mut ca = ArgsCmdLineSimpleManager::new();
+/// The max number of records to run through, when searching for the next event.
+const SEEK_EVENT_MAXRECORDS: usize = 12000;
 
-        let mut handle_mode = |iarg: usize, args: &Vec<String>| -> usize {
-            cfg.mode = args[iarg+1].to_string();
-            return 1;
-        };
-        ca.add_handler("--mode", &mut handle_mode);
+/// Build the config from the commandline args (refer Config::from_args),
+/// exiting on any error.
+fn config_load() -> Config {
+    let args: Vec<String> = std::env::args().collect();
+    match Config::from_args(&args[1..]) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("ERRR:{}:Config:{}", MTAG, err);
+            std::process::exit(11);
+        }
+    }
+}
 
-        let mut handle_src = |iarg: usize, args: &Vec<String>| -> usize {
-            cfg.src = args[iarg+1].to_string();
-            return 1;
-        };
-        ca.add_handler("--src", &mut handle_src);
+/// The file into which the team shape metrics are exported, ie next to the
+/// rcg file being played back, else in the temp dir.
+fn teamshapes_csv(cfg: &Config) -> String {
+    if cfg.mode == "rcg" {
+        let path = std::path::Path::new(&cfg.src).with_extension(TEAMSHAPES_CSV);
+        return path.to_string_lossy().to_string();
+    }
+    return std::env::temp_dir().join(TEAMSHAPES_CSV).to_string_lossy().to_string();
+}
 
-        let mut handle_saveinterval = |iarg: usize, args: &Vec<String>| -> usize {
-            cfg.save_interval = args[iarg+1].parse().unwrap();
-            return 1;
-        };
-        ca.add_handler("--save_interval", &mut handle_saveinterval);
 
-        let mut handle_saveinterval = |iarg: usize, args: &Vec<String>| -> usize {
-            cfg.fps = args[iarg+1].parse().unwrap();
-            return 1;
-        };
-        ca.add_handler("--fps", &mut handle_saveinterval);
+/// The range of the playback speed multiplier
+const SPEED_MIN: f32 = 0.1;
+const SPEED_MAX: f32 = 16.0;
 
-        let mut handle_virtball = |iarg: usize, args: &Vec<String>| -> usize {
-            cfg.fvirtball = args[iarg+1].to_string();
-            return 1;
-        };
-        ca.add_handler("--virtball", &mut handle_virtball);
+/// The reverse playback speeds (relative to normal playback) one can cycle through
+const REVERSE_SPEEDS: [f32; 5] = [1.0, 2.0, 4.0, 0.25, 0.5];
 
-        ca.process_args();
+/// The max camera zoom, and the zoom ratio applied per mouse wheel step
+const CAMERA_ZOOM_MAX: f32 = 8.0;
+const CAMERA_ZOOM_STEP: f32 = 1.25;
 
-        cfg
-    }
+/// How near (in screen normal space) to a player one needs to click, to inspect it
+const INSPECT_PICK_NDIST: f32 = 0.02;
+/// The max mouse movement (in screen normal space) between press and release,
+/// for it to be treated as a click, rather than a drag
+const INSPECT_CLICK_NMOVE: f32 = 0.005;
+/// The size of the player inspect panel, in screen normal space
+const INSPECT_PANEL_SIZE: (f32, f32) = (0.28, 0.34);
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// What the camera keeps centered on, if anything
+enum CameraFollow {
+    None,
+    Ball,
+    /// The player selected wrt event navigation
+    Player,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The playback state
+enum Playback {
+    Forward,
+    Reverse,
+    Paused,
+}
 
 struct Gui<'a> {
     /// Whether help msgbox should be shown or not in the current frame
     showhelp: bool,
-    /// Pause the playback
-    pause: bool,
+    /// Whether entities are moved in a interpolated manner over multiple frames,
+    /// or else set to the new positions directly wrt each record.
+    binterpolate: bool,
+    /// The fps to use wrt the interpolated movements mode
+    ifps: f32,
+    /// The interpolator used wrt the interpolated movements mode
+    interpolator: Interpolator,
+    /// The current playback state
+    playback: Playback,
+    /// The playback state to resume to, when unpaused
+    playback_resume: Playback,
+    /// Playback speed multiplier, independent of the render fps
+    speed: f32,
+    /// Reverse playback speed, as a index into REVERSE_SPEEDS
+    revspeed: usize,
+    /// Tracks the (fractional) records to step back, wrt reverse playback
+    revaccum: f32,
+    /// The A and B times wrt A-B loop, if set
+    abloop: (Option<usize>, Option<usize>),
     /// Current frame number
     frame: usize,
     /// Time alloted per frame
@@ -136,6 +159,33 @@ struct Gui<'a> {
     saved_virtball_csv: bool,
     /// Include Penalty Card based scoring in PerfScore or not
     inc_cardscore: bool,
+    /// The side whose passing network is shown, if any
+    passnetwork: Option<char>,
+    /// The type of event to seek to, wrt event navigation
+    evtype: EventType,
+    /// The player selected wrt player actions event navigation.
+    /// Left team players followed by right team players.
+    evplayer: usize,
+    /// The timeline strip at the bottom
+    timeline: Timeline,
+    /// Whether the timeline is being clicked+dragged on, to seek
+    timeline_seeking: bool,
+    /// The (count, last time) wrt play modes and cards, the timeline markers were built from
+    timeline_marked: ((usize, usize), (usize, usize)),
+    /// The event log side panel
+    eventlog: EventLog,
+    /// Whether the event log was clicked on, to seek
+    eventlog_clicked: bool,
+    /// The command palette
+    palette: Palette,
+    /// What the camera follows
+    camfollow: CameraFollow,
+    /// The last mouse position (in screen normal space), while panning the camera
+    campanning: Option<(f32, f32)>,
+    /// The position (in screen normal space) where the mouse was pressed, wrt click to inspect
+    inspectclick: Option<(f32, f32)>,
+    /// The player pinned for inspection, if any
+    inspect: Option<(char, usize)>,
 }
 
 impl<'a> Gui<'a> {
@@ -144,26 +194,62 @@ impl<'a> Gui<'a> {
         time::Duration::from_millis((1000.0/fps).round() as u64)
     }
 
-    /// Sync up fps to the seconds per record of the playdata source
-    #[cfg(feature="inbetween_frames")]
+    /// Sync up fps to the seconds per record of the playdata source.
+    ///
+    /// In the interpolated movements mode, the fps is independent of the
+    /// playdata source, so the fps set for this mode is used.
     fn sync_up_fps_to_spr(&mut self) {
-        self.fps_adjust(1.0);
+        let fpsadj;
+        if self.binterpolate {
+            fpsadj = self.ifps/self.pgentities.fps();
+        } else {
+            let spr = self.pdata.seconds_per_record();
+            fpsadj = (1.0/spr)/self.pgentities.fps();
+        }
+        self.fps_adjust(fpsadj);
     }
 
-    #[cfg(not(feature="inbetween_frames"))]
-    fn sync_up_fps_to_spr(&mut self) {
-        let spr = self.pdata.seconds_per_record();
-        let fpsadj = (1.0/spr)/self.pgentities.fps();
-        self.fps_adjust(fpsadj);
+    /// Switch between the interpolated and discrete movements modes.
+    ///
+    /// Any pending interpolated movement is completed, the playdata source is
+    /// informed and the fps is synced up wrt the new mode.
+    fn set_interpolate(&mut self, binterpolate: bool) {
+        if self.binterpolate {
+            self.ifps = self.pgentities.fps();
+        }
+        self.binterpolate = binterpolate;
+        self.pgentities.move_finish();
+        self.pdata.interpolate_changed(binterpolate);
+        self.sync_up_fps_to_spr();
+    }
+
+    /// Cycle through the available interpolators, wrt the interpolated movements
+    fn interpolator_cycle(&mut self) {
+        self.interpolator = self.interpolator.next();
+        self.pgentities.set_interpolator(self.interpolator);
+        if (self.interpolator == Interpolator::CatmullRom) && self.pdata.peek_record().is_none() {
+            self.pgentities.timedmsg.update_direct(&format!("Interpolator:{}:Linear, as next positions unknown", self.interpolator));
+            return;
+        }
+        self.pgentities.timedmsg.update_direct(&format!("Interpolator:{}", self.interpolator));
+    }
+
+    fn toggle_interpolate(&mut self) {
+        self.set_interpolate(!self.binterpolate);
+        if self.binterpolate {
+            self.pgentities.timedmsg.update_direct("Movements:Interpolated");
+        } else {
+            self.pgentities.timedmsg.update_direct("Movements:Discrete");
+        }
     }
 
 }
 
 impl<'a> Gui<'a> {
 
-    fn new(cfg: &Cfg, font: &'a Font) -> Gui<'a> {
+    fn new(cfg: &Config, font: Option<&'a Font>) -> Gui<'a> {
         // PGEntities
-        let mut pgentities = entities::PGEntities::new(entities::PITCH_RECT, 11, 11, cfg.fps, font);
+        let mut pgentities = entities::PGEntities::new(11, 11, cfg.fps, font, &cfg);
         pgentities.adjust_members(&cfg.fvirtball);
         // Playdata source
         let (pdata, showhelp) = pdata_source(cfg, pgentities.fps());
@@ -171,7 +257,15 @@ impl<'a> Gui<'a> {
         let ctime = time::Instant::now();
         let mut gui = Gui {
             showhelp: showhelp,
-            pause: false,
+            binterpolate: cfg.interpolate,
+            ifps: cfg.fps,
+            interpolator: Interpolator::Linear,
+            playback: Playback::Forward,
+            playback_resume: Playback::Forward,
+            speed: 1.0,
+            revspeed: 0,
+            revaccum: 0.0,
+            abloop: (None, None),
             frame: 0,
             frametime: Self::calc_frametime(cfg.fps),
             fpsframe: 0,
@@ -186,8 +280,27 @@ impl<'a> Gui<'a> {
             aidistances_summarytype: actions::SUMMARY_RELATIVE_TEAM,
             saved_virtball_csv: false,
             inc_cardscore: true,
+            passnetwork: None,
+            evtype: EventType::Goal,
+            evplayer: 0,
+            timeline: Timeline::new(cfg.timeline_rect),
+            timeline_seeking: false,
+            timeline_marked: ((0, 0), (0, 0)),
+            eventlog: EventLog::new(cfg.eventlog_rect, cfg.eventlog_nlines),
+            eventlog_clicked: false,
+            palette: Palette::new(entities::PALETTE_RECT, entities::PALETTE_NLINES),
+            camfollow: CameraFollow::None,
+            campanning: None,
+            inspectclick: None,
+            inspect: None,
         };
-        // sync up fps to spr
+        // Trails length in terms of records
+        let trailrecords = (cfg.trail/gui.pdata.seconds_per_record()).round().max(0.0) as usize;
+        gui.pgentities.set_trails(trailrecords);
+        gui.pgentities.scoreboard.set_seconds_per_record(gui.pdata.seconds_per_record());
+        // sync up movements mode and fps to spr
+        gui.pdata.interpolate_changed(gui.binterpolate);
+        gui.pgentities.set_seekable(gui.pdata.seekable());
         gui.sync_up_fps_to_spr();
         return gui;
     }
@@ -201,11 +314,28 @@ impl<'a> Gui<'a> {
     /// It inturn takes care of keeping gui internal logic, pgentities and pdata in sync wrt fps changes
     fn fps_adjust(&mut self, ratio: f32) {
         self.pgentities.fps_adjust(ratio);
-        self.pdata.fps_changed(self.pgentities.fps());
+        self.pdata.fps_changed(self.pgentities.fps()/self.speed);
         self.internal_fps_changed(self.pgentities.fps());
         eprintln!("INFO:{}:Fps:{}", MTAG, self.pgentities.fps());
     }
 
+    /// Adjust the playback speed multiplier, without changing the render fps.
+    ///
+    /// The playdata source is told about the fps wrt playdata time, so that it
+    /// can inturn provide more or less records per frame, as required.
+    fn speed_adjust(&mut self, ratio: f32) {
+        self.speed = (self.speed * ratio).clamp(SPEED_MIN, SPEED_MAX);
+        self.pdata.fps_changed(self.pgentities.fps()/self.speed);
+        self.pgentities.timedmsg.update_direct(&format!("Speed:{}x", self.speed));
+    }
+
+    /// The number of frames over which the entities should move to a new record,
+    /// in the interpolated mode, wrt the current fps and playback speed.
+    fn record_inframes(&self) -> f32 {
+        let inframes = self.pdata.seconds_per_record() * self.pgentities.fps() / self.speed;
+        return inframes.max(1.0);
+    }
+
     /// Update internal state, wrt/related-to begining of a new frame
     fn next_frame(&mut self) {
         self.frame += 1;
@@ -228,38 +358,620 @@ impl<'a> Gui<'a> {
         }
     }
 
+    /// Seek through the records of the playdata source.
+    ///
+    /// * backward: seek back by as many records wrt the playdata source, and inturn
+    ///   to the time of the record there, refer seek_back_to_time.
+    /// * forward: the skipped records are fast processed through the entities
+    ///   (and inturn the actions/analysis logic), without drawing them. So goals,
+    ///   cards, scores, ... remain same as if one had watched through them,
+    ///   irrespective of any seeking back done before.
     fn seek(&mut self, seekdelta: isize) {
+        if seekdelta >= 0 {
+            self.fast_forward(seekdelta as usize);
+            return;
+        }
+        if !self.pdata.seekable() {
+            return;
+        }
         self.pdata.seek(seekdelta);
-        self.pgentities.seek(seekdelta);
+        let ntime = match self.pdata.peek_record() {
+            Some(pu) => pu.timecounter,
+            None => self.pgentities.timecounter().saturating_sub(seekdelta.unsigned_abs()),
+        };
+        self.seek_back_to_time(ntime);
+    }
+
+    /// Seek back such that the next record got from the playdata source, is the
+    /// 1st one at or beyond the specified time.
+    ///
+    /// The actions/analysis logic inturn undoes what it had given wrt the records
+    /// at or after the time seeked to, when it sees the next record. As the players
+    /// scores, counts, ... can only be restored to a checkpoint, the playdata source
+    /// is seeked to the nearest checkpoint at or before the specified time, and the
+    /// records from there till the specified time are fast processed.
+    fn seek_back_to_time(&mut self, timecounter: usize) {
+        if !self.pdata.seekable() {
+            return;
+        }
+        let ctime = self.pgentities.timecounter();
+        let cptime = self.pgentities.actionsinfo.checkpoint_time(timecounter);
+        self.pdata.seek_to_time(cptime);
+        self.pgentities.seek(cptime as isize - ctime as isize);
+        while !self.pdata.bdone() {
+            match self.pdata.peek_record() {
+                Some(pu) if pu.timecounter < timecounter => (),
+                _ => break,
+            }
+            let pu = self.pdata.next_record();
+            self.pgentities.update(pu, true, 0.0);
+        }
+    }
+
+    /// Seek to the specified time.
+    ///
+    /// * forward: run through the records till the specified time is reached.
+    /// * backward: seek the playdata source to the specified time, and inturn
+    ///   process the record at that time, so that the entities reflect it.
+    fn seek_to_time(&mut self, timecounter: usize) {
+        if !self.pdata.seekable() {
+            return;
+        }
+        let ctime = self.pgentities.timecounter();
+        if timecounter > ctime {
+            while !self.pdata.bdone() && (self.pgentities.timecounter() < timecounter) {
+                self.fast_forward(1);
+            }
+            return;
+        }
+        self.step_back(ctime - timecounter, true, 0.0);
+    }
+
+    /// Step back by the specified number of cycles (timecounter steps), and inturn
+    /// process the record at that time, so that the entities reflect it.
+    ///
+    /// babsolute, inframes: control how the entities are moved, refer PGEntities::update.
+    fn step_back(&mut self, ncycles: usize, babsolute: bool, inframes: f32) {
+        if !self.pdata.seekable() {
+            return;
+        }
+        let ntime = self.pgentities.timecounter().saturating_sub(ncycles);
+        self.seek_back_to_time(ntime);
+        if !self.pdata.bdone() {
+            let pu = self.pdata.next_record();
+            self.pgentities.update(pu, babsolute, inframes);
+        }
+    }
+
+    /// Run through the specified number of records from the playdata source,
+    /// updating the entities immidiately (ie no interpolation) and without drawing.
+    ///
+    /// NOTE: Sources which dont support seeking (like a live server) are left alone.
+    fn fast_forward(&mut self, numrecords: usize) {
+        if !self.pdata.seekable() {
+            eprintln!("WARN:{}:FastForward:Playdata source doesnt support seeking", MTAG);
+            return;
+        }
+        for _i in 0..numrecords {
+            if self.pdata.bdone() {
+                break;
+            }
+            let pu = self.pdata.next_record();
+            self.pgentities.update(pu, true, 0.0);
+        }
     }
 
 }
 
-fn show_help(sx: &mut SdlX) {
-    let shelp = "** Help **\n\
-    \n\
-    larrow: seek back\n\
-    rarrow: seek forward\n\
-    f/F:    change fps\n\
-    p:      pause playback\n\
-    ss:     show/hide stamina\n\
-    sa:     show/hide actions\n\
-    sb:     show/hide ball\n\
-    c1:     RCLive kick-off\n\
-    c0:     RCLive init hs\n\
-    h:      hide/unhide help\n\
-    \n\
-    gameplaypgnd-rcss2d --mode rclive [--src nwaddr]\n\
-    gameplaypgnd-rcss2d --mode rcg --src <path/file.rcg>\n\
-    ...                      Save Nature Save Earth";
-
-    let vhelp: Vec<&str> = shelp.split('\n').collect();
-    sx.n_msgbox((0.3,0.2, 0.4,0.6), vhelp, Color::BLUE);
+impl<'a> Gui<'a> {
+
+    /// The (side, playerid) of the player selected wrt event navigation
+    fn evplayer_id(&self) -> (char, usize) {
+        let (lnplayers, _rnplayers) = self.pgentities.nplayers();
+        if self.evplayer < lnplayers {
+            return (entities::SIDE_L, self.evplayer);
+        }
+        return (entities::SIDE_R, self.evplayer - lnplayers);
+    }
+
+    /// Cycle through the players, wrt player actions event navigation
+    fn seek_event_player(&mut self, delta: isize) {
+        let (lnplayers, rnplayers) = self.pgentities.nplayers();
+        let nplayers = (lnplayers + rnplayers) as isize;
+        self.evplayer = (self.evplayer as isize + delta).rem_euclid(nplayers) as usize;
+        let (side, playerid) = self.evplayer_id();
+        self.pgentities.timedmsg.update_direct(&format!("EventPlayer:{}{:02}", side, playerid));
+    }
+
+    /// Seek to the next (dir +ve) or previous (dir -ve) event of the selected type.
+    ///
+    /// Previous events are found from the events/actions seen till now.
+    /// Next events are found by running through the records, till one is seen.
+    /// If no next event is found, one is taken back to where one started.
+    fn seek_event(&mut self, dir: isize) {
+        if !self.pdata.seekable() {
+            self.pgentities.timedmsg.update_direct("Event:Seek not supported");
+            return;
+        }
+        let player = self.evplayer_id();
+        let ctime = self.pgentities.timecounter();
+        let mut etime = None;
+        if dir < 0 {
+            etime = self.pgentities.gameevents.find_time_rev(&self.pgentities.actionsinfo, self.evtype, player, 0, ctime);
+            if etime.is_some() {
+                self.seek_to_time(etime.unwrap());
+            }
+        } else {
+            for _i in 0..SEEK_EVENT_MAXRECORDS {
+                if self.pdata.bdone() {
+                    break;
+                }
+                self.fast_forward(1);
+                let ntime = self.pgentities.timecounter();
+                etime = self.pgentities.gameevents.find_time_rev(&self.pgentities.actionsinfo, self.evtype, player, ctime+1, ntime+1);
+                if etime.is_some() {
+                    break;
+                }
+            }
+            if etime.is_none() {
+                self.seek_to_time(ctime);
+            }
+        }
+        if etime.is_some() {
+            self.pgentities.timedmsg.update_direct(&format!("Event:{}:{}", self.evtype, etime.unwrap()));
+        } else {
+            self.pgentities.timedmsg.update_direct(&format!("Event:{}:NotFound", self.evtype));
+        }
+    }
 
 }
 
+impl<'a> Gui<'a> {
+
+    /// Pause or resume the playback
+    fn toggle_pause(&mut self) {
+        if self.playback == Playback::Paused {
+            self.playback = self.playback_resume;
+            self.pgentities.timedmsg.update_direct("UnPaused...");
+        } else {
+            self.playback_resume = self.playback;
+            self.playback = Playback::Paused;
+            self.pgentities.timedmsg.update_direct("Paused...");
+        }
+    }
+
+    /// Switch between forward and reverse playback
+    fn toggle_reverse(&mut self) {
+        if self.playback == Playback::Reverse {
+            self.playback = Playback::Forward;
+            self.pgentities.timedmsg.update_direct("Playback:Forward");
+        } else {
+            self.playback = Playback::Reverse;
+            self.revaccum = 0.0;
+            self.pgentities.timedmsg.update_direct(&format!("Playback:Reverse:{}x", REVERSE_SPEEDS[self.revspeed]));
+        }
+    }
+
+    fn reverse_speed_cycle(&mut self) {
+        self.revspeed = (self.revspeed + 1) % REVERSE_SPEEDS.len();
+        self.pgentities.timedmsg.update_direct(&format!("ReverseSpeed:{}x", REVERSE_SPEEDS[self.revspeed]));
+    }
+
+    /// Step forward (+ve) or backward (-ve) by one cycle, pausing the playback if required.
+    fn step(&mut self, dir: isize) {
+        if self.playback != Playback::Paused {
+            self.toggle_pause();
+        }
+        let ctime = self.pgentities.timecounter();
+        if dir > 0 {
+            self.seek_to_time(ctime+1);
+        } else {
+            self.seek_to_time(ctime.saturating_sub(1));
+        }
+        self.pgentities.timedmsg.update_direct(&format!("Step:{}", self.pgentities.timecounter()));
+    }
+
+    /// Run the reverse playback logic wrt the current frame.
+    ///
+    /// The records to step back wrt the current frame, follow the playback speed
+    /// and the reverse speed. In the interpolated mode, the entities are moved to
+    /// the previous record over the frames alloted per record.
+    fn reverse_frame(&mut self) {
+        let revspeed = REVERSE_SPEEDS[self.revspeed];
+        let inbtw = self.binterpolate;
+        let nready = self.pdata.next_frame_records_ready();
+        self.revaccum += nready as f32 * revspeed;
+        let ncycles = self.revaccum.floor();
+        self.revaccum -= ncycles;
+        if ncycles >= 1.0 {
+            if self.pgentities.timecounter() == 0 {
+                self.toggle_pause();
+                return;
+            }
+            let inframes = self.record_inframes() / revspeed.min(1.0);
+            self.step_back(ncycles as usize, !inbtw, inframes);
+        }
+        if inbtw {
+            self.pgentities.next_frame();
+        }
+    }
+
+    /// Mark the A and B points wrt A-B loop, at the current time.
+    /// If both are already marked, then clear the loop.
+    fn abloop_mark(&mut self) {
+        let ctime = self.pgentities.timecounter();
+        match self.abloop {
+            (None, _) => {
+                self.abloop = (Some(ctime), None);
+                self.pgentities.timedmsg.update_direct(&format!("ABLoop:A:{}", ctime));
+            },
+            (Some(atime), None) => {
+                if ctime <= atime {
+                    self.pgentities.timedmsg.update_direct("ABLoop:B should be after A");
+                    return;
+                }
+                self.abloop = (Some(atime), Some(ctime));
+                self.pgentities.timedmsg.update_direct(&format!("ABLoop:{}-{}", atime, ctime));
+            },
+            (Some(_), Some(_)) => {
+                self.abloop = (None, None);
+                self.pgentities.timedmsg.update_direct("ABLoop:Cleared");
+            },
+        }
+    }
+
+    /// If A-B loop is active, wrap around at the ends of the marked segment,
+    /// wrt the current playback direction.
+    fn abloop_check(&mut self) {
+        if let (Some(atime), Some(btime)) = self.abloop {
+            let ctime = self.pgentities.timecounter();
+            if (self.playback == Playback::Forward) && ((ctime >= btime) || self.pdata.bdone()) {
+                self.seek_to_time(atime);
+            } else if (self.playback == Playback::Reverse) && (ctime <= atime) {
+                self.seek_to_time(btime);
+            }
+        }
+    }
+
+    /// Update the timeline wrt match length and markers for goals, cards and play mode changes.
+    ///
+    /// Play mode changes known in advance from the playdata source are used, if
+    /// available, else those seen till now are used. The markers are rebuilt only
+    /// if the play modes or cards have changed.
+    fn update_timeline(&mut self) {
+        let ctime = self.pgentities.timecounter();
+        self.timeline.set_tmax(self.pdata.timecounter_max().unwrap_or(ctime));
+        let mut playmodes = self.pdata.playmodes_index();
+        if playmodes.len() == 0 {
+            playmodes = self.pgentities.gameevents.playmodes();
+        }
+        let cards = self.pgentities.actionsinfo.cards();
+        let marked = (
+            (playmodes.len(), playmodes.last().map(|pm| pm.0).unwrap_or(0)),
+            (cards.len(), cards.last().map(|card| card.0).unwrap_or(0)),
+        );
+        if marked == self.timeline_marked {
+            return;
+        }
+        self.timeline_marked = marked;
+        self.timeline.markers_clear();
+        for (time, state) in playmodes {
+            match state {
+                GameState::Goal(_) => self.timeline.marker_add(*time, Color::MAGENTA, 1.6),
+                GameState::SetPiece(_, _) => self.timeline.marker_add(*time, Color::CYAN, 0.6),
+                _ => self.timeline.marker_add(*time, Color::GRAY, 0.4),
+            }
+        }
+        for card in cards {
+            let color = if card.3 == playdata::Card::Red { Color::RED } else { Color::YELLOW };
+            self.timeline.marker_add(card.0, color, 1.0);
+        }
+    }
+
+    /// Handle mouse button press/drag/release, wrt the timeline based seeking
+    fn timeline_mouse(&mut self, pev: keys::ProgramEvent) {
+        match pev {
+            keys::ProgramEvent::MouseDown(nx, ny) => {
+                if self.timeline.contains(nx, ny) {
+                    self.timeline_seeking = true;
+                    self.seek_to_time(self.timeline.nx2time(nx));
+                }
+            },
+            keys::ProgramEvent::MouseDrag(nx, _ny) => {
+                if self.timeline_seeking {
+                    self.seek_to_time(self.timeline.nx2time(nx));
+                }
+            },
+            keys::ProgramEvent::MouseUp(_nx, _ny) => self.timeline_seeking = false,
+            _ => (),
+        }
+    }
+
+    /// Update the event log entries, wrt the events seen till now and how far
+    /// back the log has been scrolled.
+    fn update_eventlog(&mut self) {
+        if !self.eventlog.bshow {
+            return;
+        }
+        let timecounter = self.pgentities.timecounter();
+        let entries = self.pgentities.gameevents.log_entries(&self.pgentities.actionsinfo, self.eventlog.nlines(), self.eventlog.scroll(), timecounter);
+        self.eventlog.set_entries(entries);
+    }
+
+    fn toggle_eventlog(&mut self) {
+        self.eventlog.bshow = !self.eventlog.bshow;
+        if self.eventlog.bshow {
+            self.pgentities.timedmsg.update_direct("EventLog:Show");
+        } else {
+            self.pgentities.timedmsg.update_direct("EventLog:Hide");
+        }
+    }
+
+    /// Handle mouse button press/release, wrt seeking to the event clicked on
+    /// in the event log.
+    fn eventlog_mouse(&mut self, pev: keys::ProgramEvent) {
+        match pev {
+            keys::ProgramEvent::MouseDown(nx, ny) => {
+                if self.eventlog.contains(nx, ny) {
+                    self.eventlog_clicked = true;
+                    if let Some(time) = self.eventlog.ny2time(ny) {
+                        self.seek_to_time(time);
+                        self.pgentities.timedmsg.update_direct(&format!("EventLog:Seek:{}", time));
+                    }
+                }
+            },
+            keys::ProgramEvent::MouseUp(_nx, _ny) => self.eventlog_clicked = false,
+            _ => (),
+        }
+    }
+
+    /// Handle mouse wheel, scrolling the event log if the mouse pointer is over it,
+    /// else zooming the camera.
+    fn mouse_wheel(&mut self, sx: &mut SdlX, steps: i32) {
+        let ms = sx.ep.mouse_state();
+        let (mx, my) = sx.n2s.o2d((ms.x() as f32, ms.y() as f32));
+        if self.eventlog.contains(mx, my) {
+            self.eventlog.scroll_by(steps as isize);
+            return;
+        }
+        self.camera_zoom(sx, steps);
+    }
+
+}
+
+impl<'a> Gui<'a> {
+
+    /// Set the camera zoom and center, with the center clamped such that
+    /// the camera view doesnt go beyond the normal space.
+    fn camera_set(&mut self, sx: &mut SdlX, zoom: f32, center: (f32, f32)) {
+        let zoom = zoom.clamp(1.0, CAMERA_ZOOM_MAX);
+        let hview = 0.5/zoom;
+        let cx = center.0.clamp(hview, 1.0-hview);
+        let cy = center.1.clamp(hview, 1.0-hview);
+        sx.camera_set(zoom, (cx, cy));
+    }
+
+    /// Zoom in (+ve steps) or out (-ve steps), keeping the point under the mouse
+    /// pointer fixed on the screen.
+    fn camera_zoom(&mut self, sx: &mut SdlX, steps: i32) {
+        let (zoom, (cx, cy)) = sx.camera();
+        let nzoom = (zoom * CAMERA_ZOOM_STEP.powi(steps)).clamp(1.0, CAMERA_ZOOM_MAX);
+        let ms = sx.ep.mouse_state();
+        let (mx, my) = sx.n2s.o2d((ms.x() as f32, ms.y() as f32));
+        // The world point under the mouse pointer
+        let wx = cx + (mx-0.5)/zoom;
+        let wy = cy + (my-0.5)/zoom;
+        self.camera_set(sx, nzoom, (wx - (mx-0.5)/nzoom, wy - (my-0.5)/nzoom));
+        self.pgentities.timedmsg.update_direct(&format!("Camera:Zoom:{:.2}x", nzoom));
+    }
+
+    /// Cycle through what the camera follows
+    fn camera_follow_cycle(&mut self) {
+        self.camfollow = match self.camfollow {
+            CameraFollow::None => CameraFollow::Ball,
+            CameraFollow::Ball => CameraFollow::Player,
+            CameraFollow::Player => CameraFollow::None,
+        };
+        if self.camfollow == CameraFollow::Player {
+            let (side, playerid) = self.evplayer_id();
+            self.pgentities.timedmsg.update_direct(&format!("Camera:Follow:{}{:02}", side, playerid));
+        } else {
+            self.pgentities.timedmsg.update_direct(&format!("Camera:Follow:{:?}", self.camfollow));
+        }
+    }
+
+    /// Cycle through the heatmaps, between none, ball, left team, right team
+    /// and the player selected wrt event navigation.
+    fn heatmap_cycle(&mut self) {
+        let (side, playerid) = self.evplayer_id();
+        let target = match self.pgentities.heatmaps.target() {
+            HeatmapTarget::None => HeatmapTarget::Ball,
+            HeatmapTarget::Ball => HeatmapTarget::Team(entities::SIDE_L),
+            HeatmapTarget::Team(entities::SIDE_L) => HeatmapTarget::Team(entities::SIDE_R),
+            HeatmapTarget::Team(_) => HeatmapTarget::Player(side, playerid),
+            HeatmapTarget::Player(_, _) => HeatmapTarget::None,
+        };
+        self.pgentities.heatmaps.set_target(target);
+        self.pgentities.timedmsg.update_direct(&format!("Heatmap:{}", target));
+    }
+
+    /// Cycle through the time windows wrt the heatmap
+    fn heatmap_window_cycle(&mut self) {
+        let window = self.pgentities.heatmaps.window_cycle();
+        if window == 0 {
+            self.pgentities.timedmsg.update_direct("Heatmap:Window:All");
+        } else {
+            let secs = window as f32 * self.pdata.seconds_per_record();
+            self.pgentities.timedmsg.update_direct(&format!("Heatmap:Window:{}", duration_str(secs)));
+        }
+    }
+
+    /// Reset the camera to show the full pitch
+    fn camera_reset(&mut self, sx: &mut SdlX) {
+        self.camfollow = CameraFollow::None;
+        self.campanning = None;
+        self.camera_set(sx, 1.0, (0.5, 0.5));
+        self.pgentities.timedmsg.update_direct("Camera:Reset");
+    }
+
+    /// Keep the camera centered on what it follows, if any
+    fn camera_follow(&mut self, sx: &mut SdlX) {
+        let center = match self.camfollow {
+            CameraFollow::None => return,
+            CameraFollow::Ball => self.pgentities.ball_pos(),
+            CameraFollow::Player => {
+                let (side, playerid) = self.evplayer_id();
+                self.pgentities.player_pos(side, playerid)
+            },
+        };
+        let (zoom, _center) = sx.camera();
+        self.camera_set(sx, zoom, center);
+    }
+
+    /// Convert the given position in screen normal space to world normal space,
+    /// wrt the current camera zoom and center.
+    fn screen2world(&self, sx: &SdlX, npos: (f32, f32)) -> (f32, f32) {
+        let (zoom, (cx, cy)) = sx.camera();
+        return (cx + (npos.0-0.5)/zoom, cy + (npos.1-0.5)/zoom);
+    }
+
+    /// Convert the given position in world normal space to screen normal space,
+    /// wrt the current camera zoom and center.
+    fn world2screen(&self, sx: &SdlX, npos: (f32, f32)) -> (f32, f32) {
+        let (zoom, (cx, cy)) = sx.camera();
+        return ((npos.0-cx)*zoom + 0.5, (npos.1-cy)*zoom + 0.5);
+    }
+
+    /// Handle mouse button press/release, wrt click to inspect a player.
+    /// Clicking on a player pins it for inspection, while clicking elsewhere
+    /// (other than the timeline) unpins it.
+    fn inspect_mouse(&mut self, sx: &SdlX, pev: keys::ProgramEvent) {
+        match pev {
+            keys::ProgramEvent::MouseDown(nx, ny) => {
+                if !self.timeline_seeking && !self.eventlog_clicked {
+                    self.inspectclick = Some((nx, ny));
+                }
+            },
+            keys::ProgramEvent::MouseUp(nx, ny) => {
+                if let Some((px, py)) = self.inspectclick.take() {
+                    if ((nx-px).abs() > INSPECT_CLICK_NMOVE) || ((ny-py).abs() > INSPECT_CLICK_NMOVE) {
+                        return;
+                    }
+                    let (zoom, _center) = sx.camera();
+                    let wpos = self.screen2world(sx, (nx, ny));
+                    self.inspect = self.pgentities.player_nearest(wpos, INSPECT_PICK_NDIST/zoom);
+                    match self.inspect {
+                        Some((side, playerid)) => self.pgentities.timedmsg.update_direct(&format!("Inspect:{}{:02}", side, playerid)),
+                        None => self.pgentities.timedmsg.update_direct("Inspect:None"),
+                    }
+                }
+            },
+            _ => (),
+        }
+    }
+
+    /// Show the details of the player pinned for inspection (if any), in a panel
+    /// next to the player, so that it follows the player around.
+    fn show_inspect(&self, sx: &mut SdlX) {
+        let (side, playerid) = match self.inspect {
+            Some(player) => player,
+            None => return,
+        };
+        let ppos = self.world2screen(sx, self.pgentities.player_pos(side, playerid));
+        let (pw, ph) = INSPECT_PANEL_SIZE;
+        let px = (ppos.0 + 0.02).min(1.0 - pw).max(0.0);
+        let py = (ppos.1 - ph/2.0).min(1.0 - ph).max(0.0);
+        let vdetails = self.pgentities.player_details(side, playerid, self.inc_cardscore);
+        let vdetails: Vec<&str> = vdetails.iter().map(|s| s.as_str()).collect();
+        sx.n_msgbox((px, py, pw, ph), vdetails, Color::BLUE);
+    }
+
+    /// Handle mouse button press/drag/release, wrt panning the camera.
+    /// Panning stops the camera from following anything.
+    fn camera_mouse(&mut self, sx: &mut SdlX, pev: keys::ProgramEvent) {
+        match pev {
+            keys::ProgramEvent::MouseDown(nx, ny) => {
+                if !self.timeline_seeking && !self.eventlog_clicked {
+                    self.campanning = Some((nx, ny));
+                }
+            },
+            keys::ProgramEvent::MouseDrag(nx, ny) => {
+                if let Some((px, py)) = self.campanning {
+                    let (zoom, (cx, cy)) = sx.camera();
+                    self.camfollow = CameraFollow::None;
+                    self.camera_set(sx, zoom, (cx - (nx-px)/zoom, cy - (ny-py)/zoom));
+                    self.campanning = Some((nx, ny));
+                }
+            },
+            keys::ProgramEvent::MouseUp(_nx, _ny) => self.campanning = None,
+            _ => (),
+        }
+    }
+
+}
+
+/// The help wrt mouse and cmdline, shown after the key bindings
+const HELP_EXTRA: [&str; 7] = [
+    "wheel:  zoom in/out",
+    "drag:   pan",
+    "click:  inspect/unpin player",
+    "",
+    "gameplaypgnd-rcss2d --mode rclive [--src nwaddr]",
+    "gameplaypgnd-rcss2d --mode rcg --src <path/file.rcg>",
+    "...                      Save Nature Save Earth",
+];
+
+/// The width (in chars) of each column in the help box
+const HELP_COLUMN_CHARS: usize = 40;
+
+/// Show the help, generated from the key bindings, in two columns
+fn show_help(sx: &mut SdlX, keymap: &keys::KeyMap) {
+    let vkeys = keymap.help_lines();
+    let nrows = (vkeys.len()+1)/2;
+    let mut vlines = vec!["** Help **".to_string(), "".to_string()];
+    for i in 0..nrows {
+        let left = &vkeys[i];
+        let right = vkeys.get(i+nrows).map(|s| s.as_str()).unwrap_or("");
+        vlines.push(format!("{:w$}{}", left, right, w=HELP_COLUMN_CHARS));
+    }
+    vlines.push("".to_string());
+    for line in HELP_EXTRA {
+        vlines.push(line.to_string());
+    }
+    let vhelp: Vec<&str> = vlines.iter().map(|s| s.as_str()).collect();
+    sx.n_msgbox((0.1,0.05, 0.8,0.9), vhelp, Color::BLUE);
+
+}
+
+/// The given duration in seconds, as a short string like 30s, 2m30s or 5m
+fn duration_str(secs: f32) -> String {
+    let isecs = secs.round() as usize;
+    if isecs < 60 {
+        return format!("{}s", isecs);
+    }
+    if isecs % 60 == 0 {
+        return format!("{}m", isecs/60);
+    }
+    return format!("{}m{:02}s", isecs/60, isecs%60);
+}
+
+/// Show the event picker, wrt event navigation, with the keys bound under the
+/// given prefix (mode) key, as got from the key map.
+fn show_event_picker(sx: &mut SdlX, gui: &Gui, keymap: &keys::KeyMap, prefix: Keycode) {
+    let mut vlines = vec!["** Events **".to_string(), "".to_string()];
+    for line in keymap.prefix_help_lines(prefix) {
+        vlines.push(format!("    {}", line));
+    }
+    let (side, playerid) = gui.evplayer_id();
+    vlines.push("".to_string());
+    vlines.push(format!("    Player: {}{:02}", side, playerid));
+    vlines.push(format!("    Selected: {}", gui.evtype));
+    let vevents: Vec<&str> = vlines.iter().map(|s| s.as_str()).collect();
+    sx.n_msgbox((0.3,0.2, 0.4,0.6), vevents, Color::BLUE);
+}
+
 #[allow(dead_code)]
-fn test_me(font: &Font) {
+fn test_me(font: Option<&Font>) {
     testlib::test_ncolor();
     testlib::test_gentity(font);
 }
@@ -267,15 +979,15 @@ fn test_me(font: &Font) {
 fn identify() {
     println!("INFO:{}:GamePlay Playground", MTAG);
     if cfg!(feature = "inbetween_frames") {
-        println!("INFO:{}:Mode: InBetween Frames", MTAG);
+        println!("INFO:{}:Default Mode: InBetween Frames", MTAG);
     } else {
-        println!("INFO:{}:Mode: OnlyProvided Frames", MTAG);
+        println!("INFO:{}:Default Mode: OnlyProvided Frames", MTAG);
     }
 }
 
 ///
 /// Setup the playdata source based on passed args,
-/// which have been processed into a Cfg struct.
+/// which have been processed into the Config.
 /// * mode:rclive: connect to a running rcssserver
 ///   * if src specified, use has nw address of server to connect to
 ///   * else use a default nw address specified in the program
@@ -284,7 +996,7 @@ fn identify() {
 ///
 /// Return the playdata source and whether help msgbox should be shown
 ///
-fn pdata_source(cfg: &Cfg, fps: f32) -> (Box<dyn PlayData>, bool) {
+fn pdata_source(cfg: &Config, fps: f32) -> (Box<dyn PlayData>, bool) {
     if cfg.mode == "rclive" {
         let nwaddr;
         if cfg.src.len() > 2 {
@@ -308,46 +1020,54 @@ fn main() {
     log_init();
     identify();
 
+    let cfg = config_load();
+
     // SDL related setup
-    let ttfx = sdl2::ttf::init().unwrap();
-    let font = ttfx.load_font(sdlx::TTF_FONT, sdlx::TTF_FONT_SIZE);
-    if font.is_err() {
-        let err = font.err().unwrap();
-        eprintln!("ERRR:{}:Loading font[{}], install it or update font in sdlx.rs:{}", MTAG, sdlx::TTF_FONT, err);
-        std::process::exit(10);
-    }
-    let font = font.unwrap();
+    // The configured font, else any system ttf font, else the builtin bitmap font is used
+    let ttfx = match sdl2::ttf::init() {
+        Ok(ttfx) => Some(ttfx),
+        Err(err) => {
+            eprintln!("WARN:{}:TTF init failed, using the builtin bitmap font:{}", MTAG, err);
+            None
+        }
+    };
+    let font = ttfx.as_ref().and_then(|ttfx| sdlx::load_ttf_font(ttfx, &cfg.font_file, cfg.font_size));
     let mut sx = sdlx::SdlX::init_plus("GamePlayPGND", entities::BASE_SCREEN_WIDTH, entities::BASE_SCREEN_HEIGHT, false);
-    let (prgw, prgh) = sdlx::get_prg_resolution();
+    // Keep the pitch aspect correct, by mapping normal space to a viewport with same aspect as rcss space
+    let ((rx1, ry1), (rx2, ry2)) = rcss::RCSS_RECT;
+    sx.set_naspect(Some((rx2-rx1)/(ry2-ry1)));
 
-    let cfg = Cfg::load();
     // Get the gui program related entity
-    let mut gui = Gui::new(&cfg, &font);
+    let mut gui = Gui::new(&cfg, font.as_ref());
 
     // The main loop of the program starts now
     let mut dcolor = 20;
     let mut skey = String::new();
+    // The program event got by running a command from the command palette
+    let mut palette_pev = None;
     'mainloop: loop {
         gui.next_frame();
         // Clear the background
-        sx.wc.set_draw_color(entities::screen_color_bg_rel(dcolor, 0, 0));
-        sx.wc.clear();
-        sx.n_msg(entities::MSG_FPS_POS.0, entities::MSG_FPS_POS.1, &format!("[{}] [{},{}]", skey, &gui.pgentities.fps().round(), gui.actualfps), sdlx::Color::BLUE);
+        sx.clear_viewport(entities::screen_color_bg_rel(cfg.color_bg, dcolor, 0, 0));
+        sx.n_msg(cfg.msg_fps_pos.0, cfg.msg_fps_pos.1, &format!("[{}] [{},{}] [{}x]", skey, &gui.pgentities.fps().round(), gui.actualfps, gui.speed), sdlx::Color::BLUE);
 
         // handle any pending/queued program events
         'eventloop: loop {
-            let prgev= keys::get_programevents(&mut sx, &mut skey);
+            let prgev = match palette_pev.take() {
+                Some(pev) => pev,
+                None => keys::get_programevents(&mut sx, &mut skey, &cfg),
+            };
             match prgev {
                 keys::ProgramEvent::None => break 'eventloop,
-                keys::ProgramEvent::Pause => {
-                    gui.pause = !gui.pause;
-                    if gui.pause {
-                        gui.pgentities.timedmsg.update_direct("Paused...");
-                    } else {
-                        gui.pgentities.timedmsg.update_direct("UnPaused...");
-                    }
-                },
+                keys::ProgramEvent::Pause => gui.toggle_pause(),
+                keys::ProgramEvent::StepForward => gui.step(1),
+                keys::ProgramEvent::StepBackward => gui.step(-1),
+                keys::ProgramEvent::ToggleReverse => gui.toggle_reverse(),
+                keys::ProgramEvent::ReverseSpeedCycle => gui.reverse_speed_cycle(),
+                keys::ProgramEvent::ABLoopMark => gui.abloop_mark(),
                 keys::ProgramEvent::BackgroundColorChange => dcolor = dcolor.wrapping_add(20),
+                keys::ProgramEvent::WindowResized => sx.handle_resize(),
+                keys::ProgramEvent::ToggleFullscreen => sx.toggle_fullscreen(),
                 keys::ProgramEvent::ToggleShowHelp => gui.showhelp = !gui.showhelp,
                 keys::ProgramEvent::ToggleShowBall => {
                     gui.pgentities.showball = !gui.pgentities.showball;
@@ -360,11 +1080,43 @@ fn main() {
                 keys::ProgramEvent::ToggleShowActions => gui.pgentities.toggle_bshowactions(),
                 keys::ProgramEvent::ToggleShowStamina => gui.pgentities.toggle_bshowstamina(),
                 keys::ProgramEvent::ToggleShowCards => gui.pgentities.toggle_bshowcards(),
+                keys::ProgramEvent::ToggleShowBody => gui.pgentities.toggle_bshowbody(),
+                keys::ProgramEvent::ToggleShowNeck => gui.pgentities.toggle_bshowneck(),
+                keys::ProgramEvent::ToggleShowViewCone => gui.pgentities.toggle_bshowviewcone(),
+                keys::ProgramEvent::ToggleShowTrails => gui.pgentities.toggle_bshowtrails(),
+                keys::ProgramEvent::HeatmapCycle => gui.heatmap_cycle(),
+                keys::ProgramEvent::HeatmapWindowCycle => gui.heatmap_window_cycle(),
+                keys::ProgramEvent::PitchControlCycle => gui.pgentities.pitchcontrol_cycle(),
+                keys::ProgramEvent::ToggleShowOffside => gui.pgentities.toggle_bshowoffside(),
+                keys::ProgramEvent::ToggleShowShapes => gui.pgentities.toggle_bshowshapes(),
+                keys::ProgramEvent::ToggleShowEventLog => gui.toggle_eventlog(),
+                keys::ProgramEvent::ToggleShowMarkers => gui.pgentities.toggle_bshowmarkers(),
+                keys::ProgramEvent::MarkersWindowCycle => gui.pgentities.markers_window_cycle(),
+                keys::ProgramEvent::MarkersActionCycle => gui.pgentities.markers_action_cycle(),
+                keys::ProgramEvent::MarkersPlayerToggle => {
+                    let (side, playerid) = gui.evplayer_id();
+                    gui.pgentities.markers_player_toggle(side, playerid);
+                },
+                keys::ProgramEvent::CommandPalette => gui.palette.open(),
+                keys::ProgramEvent::PaletteInput(text) => gui.palette.input(&text),
+                keys::ProgramEvent::PaletteBackspace => gui.palette.backspace(),
+                keys::ProgramEvent::PaletteMove(delta) => gui.palette.move_by(delta, &cfg.keymap),
+                keys::ProgramEvent::PaletteRun => palette_pev = gui.palette.run(&cfg.keymap),
+                keys::ProgramEvent::PaletteClose => gui.palette.close(),
                 keys::ProgramEvent::SeekBackward => gui.seek(-50),
                 keys::ProgramEvent::SeekForward => gui.seek(50),
+                keys::ProgramEvent::SeekEventSelect(evtype) => {
+                    gui.evtype = evtype;
+                    gui.pgentities.timedmsg.update_direct(&format!("EventType:{}", evtype));
+                },
+                keys::ProgramEvent::SeekEventPlayer(delta) => gui.seek_event_player(delta),
+                keys::ProgramEvent::SeekEvent(dir) => gui.seek_event(dir),
                 keys::ProgramEvent::AdjustFPS(ratio) => {
                     gui.fps_adjust(ratio);
                 },
+                keys::ProgramEvent::AdjustSpeed(ratio) => gui.speed_adjust(ratio),
+                keys::ProgramEvent::ToggleInterpolate => gui.toggle_interpolate(),
+                keys::ProgramEvent::InterpolatorCycle => gui.interpolator_cycle(),
                 keys::ProgramEvent::SendRecordCoded(code) => gui.pdata.send_record_coded(code),
                 keys::ProgramEvent::DumpPGEntities => eprintln!("DBUG:{}:Entities:{:#?}", MTAG, gui.pgentities),
                 keys::ProgramEvent::DumpAIScoresSummary(summarytype) => {
@@ -388,33 +1140,71 @@ fn main() {
                         gui.pgentities.timedmsg.update_direct(&format!("DistBars:{}", st));
                     }
                 },
+                keys::ProgramEvent::DumpPassNetworkCycle => {
+                    gui.passnetwork = match gui.passnetwork {
+                        None => Some(entities::SIDE_L),
+                        Some(entities::SIDE_L) => Some(entities::SIDE_R),
+                        Some(_) => None,
+                    };
+                    match gui.passnetwork {
+                        Some(side) => gui.pgentities.timedmsg.update_direct(&format!("PassNetwork:{}", side)),
+                        None => gui.pgentities.timedmsg.update_direct("PassNetwork:Hide"),
+                    }
+                },
+                keys::ProgramEvent::DumpTeamShapes => gui.pgentities.export_shapes(&teamshapes_csv(&cfg)),
                 keys::ProgramEvent::DumpIncCardScore => {
                     gui.inc_cardscore = !gui.inc_cardscore;
                     let smsg = if gui.inc_cardscore { "CardScore:Include" } else { "CardScore:Exclude" };
                     gui.pgentities.timedmsg.update_direct(smsg);
                 },
+                keys::ProgramEvent::MouseDown(_, _) | keys::ProgramEvent::MouseDrag(_, _) | keys::ProgramEvent::MouseUp(_, _) => {
+                    gui.timeline_mouse(prgev.clone());
+                    gui.eventlog_mouse(prgev.clone());
+                    gui.inspect_mouse(&sx, prgev.clone());
+                    gui.camera_mouse(&mut sx, prgev);
+                },
+                keys::ProgramEvent::MouseWheel(steps) => gui.mouse_wheel(&mut sx, steps),
+                keys::ProgramEvent::CameraFollowCycle => gui.camera_follow_cycle(),
+                keys::ProgramEvent::CameraReset => gui.camera_reset(&mut sx),
                 keys::ProgramEvent::Quit => break 'mainloop,
                 keys::ProgramEvent::NeedMore => (),
             }
         }
 
         // Update the entities
-        if !gui.pause {
+        if gui.playback == Playback::Reverse {
+            gui.reverse_frame();
+        }
+        if gui.playback == Playback::Forward {
             if !gui.pdata.bdone() {
-                if cfg!(feature = "inbetween_frames") {
-                    if gui.pdata.next_frame_is_record_ready() {
+                let nready = gui.pdata.next_frame_records_ready();
+                if gui.binterpolate {
+                    for _i in 0..nready {
+                        if gui.pdata.bdone() {
+                            break;
+                        }
                         let pu = gui.pdata.next_record();
                         ldebug!(&format!("DBUG:{}:{:?}", MTAG, pu));
-                        gui.pgentities.update(pu, false, gui.pdata.seconds_per_record() * gui.pgentities.fps());
+                        gui.pgentities.update(pu, false, gui.record_inframes());
                         //eprintln!("DBUG:GPPGND:Main:{}:Update called", _frame);
                     }
+                    if (nready > 0) && (gui.interpolator == Interpolator::CatmullRom) {
+                        if let Some(npu) = gui.pdata.peek_record() {
+                            gui.pgentities.update_next(&npu);
+                        }
+                    }
                     // TODO: Need to let this run for Fps frames ideally, even after bdone is set
                     // Or Rcg needs to be udpated to set bdone after a second of ending or so ...
                     gui.pgentities.next_frame();
                     //eprintln!("DBUG:GPPGND:Main:{}:NextFrame called", _frame);
                 } else {
-                    let pu = gui.pdata.next_record();
-                    gui.pgentities.update(pu, true, 0.0);
+                    for _i in 0..nready {
+                        if gui.pdata.bdone() {
+                            break;
+                        }
+                        let pu = gui.pdata.next_record();
+                        gui.pgentities.update(pu, true, 0.0);
+                    }
                 }
             } else {
                 if !gui.saved_virtball_csv {
@@ -423,12 +1213,26 @@ fn main() {
                 }
             }
         }
+        gui.abloop_check();
+        gui.pgentities.update_overlays();
 
         // Draw entities
+        gui.camera_follow(&mut sx);
         gui.pgentities.draw(&mut sx);
+        gui.update_timeline();
+        gui.timeline.draw(&mut sx, gui.pgentities.timecounter());
+        gui.update_eventlog();
+        gui.eventlog.draw(&mut sx);
+        gui.show_inspect(&mut sx);
         if gui.showhelp {
-            show_help(&mut sx);
+            show_help(&mut sx, &cfg.keymap);
         }
+        if let Some(prefix) = cfg.keymap.prefix_named(&skey) {
+            if cfg.keymap.is_event_prefix(prefix) {
+                show_event_picker(&mut sx, &gui, &cfg.keymap, prefix);
+            }
+        }
+        gui.palette.draw(&mut sx, &cfg.keymap);
 
         // Draw info
         if gui.showaiscores {
@@ -437,12 +1241,19 @@ fn main() {
         if gui.showaidistances {
             gui.pgentities.actionsinfo.summary_dist_sdl(&mut sx, gui.aidistances_summarytype);
         }
+        if let Some(side) = gui.passnetwork {
+            sx.space_world();
+            let color = gui.pgentities.team_color(side);
+            gui.pgentities.actionsinfo.summary_passnetwork_sdl(&mut sx, side, color);
+            sx.space_screen();
+        }
 
         // Present screen update to user
         sx.wc.present();
 
         // Save raw screen data
         if (cfg.save_interval > 0) && ((gui.frame % cfg.save_interval) == 0) {
+            let (prgw, prgh) = sdlx::get_prg_resolution();
             let imgdata = sx.wc.read_pixels(Some(Rect::new(0,0,prgw,prgh)), sdl2::pixels::PixelFormatEnum::RGB24).unwrap();
             std::fs::write(&format!("/tmp/gppgnd{:04}.rgb", gui.frame), imgdata).unwrap();
         }
@@ -452,3 +1263,192 @@ fn main() {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use playdata::{PlayUpdate, PlayerData, Action, Card};
+
+    const TMAX: usize = 120;
+    const NPLAYERS: i32 = 3;
+
+    /// A seekable playdata source, with a synthetic game, where the players
+    /// move around and kick the ball around both the teams, along with play
+    /// mode changes, a goal and a card.
+    struct Script {
+        time: usize,
+        playmodes: Vec<(usize, GameState)>,
+    }
+
+    impl Script {
+
+        fn new() -> Script {
+            Script {
+                time: 0,
+                playmodes: Vec::new(),
+            }
+        }
+
+        fn record(time: usize) -> PlayUpdate {
+            let mut pu = PlayUpdate::new();
+            pu.timecounter = time;
+            pu.state = match time {
+                1 => GameState::PlayOn,
+                61 => GameState::Goal(entities::SIDE_L),
+                62 => GameState::PlayOn,
+                _ => GameState::None,
+            };
+            pu.ball = (((time*5) % 100) as f32 / 100.0, ((time*11) % 100) as f32 / 100.0);
+            for pi in 0..NPLAYERS {
+                for side in [entities::SIDE_L, entities::SIDE_R] {
+                    let idx = if side == entities::SIDE_L { pi } else { NPLAYERS + pi } as usize;
+                    let mut card = Card::None;
+                    if (time >= 45) && (idx == 4) {
+                        card = Card::Yellow;
+                    }
+                    let bkick = (time % 4 == 0) && ((time/4) % (2*NPLAYERS as usize) == idx);
+                    let pos = (((time*7 + idx*13) % 100) as f32 / 100.0, ((time*3 + idx*29) % 100) as f32 / 100.0);
+                    let vpd = vec![
+                        PlayerData::Card(card),
+                        PlayerData::Action(if bkick { Action::Kick(true) } else { Action::None }),
+                        PlayerData::Pos(pos.0, pos.1),
+                    ];
+                    if side == entities::SIDE_L {
+                        pu.lteamcoded.push((pi, vpd));
+                    } else {
+                        pu.rteamcoded.push((pi, vpd));
+                    }
+                }
+            }
+            return pu;
+        }
+
+    }
+
+    impl PlayData for Script {
+
+        fn seconds_per_record(&self) -> f32 {
+            return rcss::SECONDS_PER_RECORD;
+        }
+
+        fn fps_changed(&mut self, _fps: f32) {
+        }
+
+        fn interpolate_changed(&mut self, _binterpolate: bool) {
+        }
+
+        fn next_frame_records_ready(&mut self) -> usize {
+            return 1;
+        }
+
+        fn next_record(&mut self) -> PlayUpdate {
+            let pu = Self::record(self.time);
+            self.time += 1;
+            return pu;
+        }
+
+        fn peek_record(&mut self) -> Option<PlayUpdate> {
+            if self.bdone() {
+                return None;
+            }
+            return Some(Self::record(self.time));
+        }
+
+        fn seek(&mut self, seekdelta: isize) {
+            self.time = (self.time as isize + seekdelta).clamp(0, TMAX as isize) as usize;
+        }
+
+        fn seek_to_time(&mut self, timecounter: usize) {
+            self.time = timecounter.min(TMAX);
+        }
+
+        fn timecounter_max(&self) -> Option<usize> {
+            return Some(TMAX-1);
+        }
+
+        fn playmodes_index(&self) -> &[(usize, GameState)] {
+            return &self.playmodes;
+        }
+
+        fn seekable(&self) -> bool {
+            return true;
+        }
+
+        fn bdone(&self) -> bool {
+            return self.time >= TMAX;
+        }
+
+        fn send_record(&mut self, _buf: &[u8]) {
+        }
+
+        fn send_record_coded(&mut self, _code: isize) {
+        }
+
+    }
+
+    fn gui_scripted<'a>(cfg: &Config) -> Gui<'a> {
+        let mut gui = Gui::new(cfg, None);
+        gui.pdata = Box::new(Script::new());
+        gui.pgentities.set_seekable(true);
+        return gui;
+    }
+
+    /// Play through the remaining records, without interpolation.
+    fn play(gui: &mut Gui) {
+        while !gui.pdata.bdone() {
+            let pu = gui.pdata.next_record();
+            gui.pgentities.update(pu, true, 0.0);
+        }
+    }
+
+    /// The actions info, game events and heatmaps state
+    fn snapshot(gui: &Gui) -> (String, String, String) {
+        let pge = &gui.pgentities;
+        return (format!("{:?}", pge.actionsinfo), format!("{:?}", pge.gameevents), format!("{:?}", pge.heatmaps));
+    }
+
+    #[test]
+    fn fast_forward_matches_playthrough() {
+        let cfg = Config::default();
+        let mut played = gui_scripted(&cfg);
+        play(&mut played);
+        let expected = snapshot(&played);
+
+        let mut seeked = gui_scripted(&cfg);
+        for _i in 0..10 {
+            let pu = seeked.pdata.next_record();
+            seeked.pgentities.update(pu, true, 0.0);
+        }
+        seeked.seek(53);
+        assert_eq!(seeked.pgentities.timecounter(), 62);
+        play(&mut seeked);
+        assert_eq!(snapshot(&seeked), expected);
+
+        let mut seeked = gui_scripted(&cfg);
+        seeked.seek_to_time(90);
+        assert_eq!(seeked.pgentities.timecounter(), 90);
+        play(&mut seeked);
+        assert_eq!(snapshot(&seeked), expected);
+    }
+
+    #[test]
+    fn seek_back_matches_playthrough() {
+        let cfg = Config::default();
+        let mut played = gui_scripted(&cfg);
+        play(&mut played);
+        let expected = snapshot(&played);
+
+        let mut seeked = gui_scripted(&cfg);
+        play(&mut seeked);
+        seeked.seek_to_time(77);
+        assert_eq!(seeked.pgentities.timecounter(), 77);
+        seeked.seek(-40);
+        assert_eq!(seeked.pdata.peek_record().unwrap().timecounter, 38);
+        seeked.step_back(20, true, 0.0);
+        assert_eq!(seeked.pgentities.timecounter(), 17);
+        play(&mut seeked);
+        assert_eq!(snapshot(&seeked), expected);
+    }
+
+}
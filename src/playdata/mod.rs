@@ -35,11 +35,19 @@ pub enum Action {
     Others(usize),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 /// Player related data
 pub enum PlayerData {
     Pos(f32,f32),
+    /// Velocity (per record) in normal 0.0-1.0 space
+    Vel(f32,f32),
     Stamina(f32),
+    /// Body direction in degrees, clockwise from +ve x-axis (ie as in rcss)
+    Body(f32),
+    /// Neck (head) direction in degrees, relative to the body direction
+    Neck(f32),
+    /// View width in degrees
+    ViewWidth(f32),
     Card(Card),
     Action(Action),
 }
@@ -50,29 +58,50 @@ pub type VPlayerData = Vec<PlayerData>;
 
 pub type PlayerCodedData = (TPlayerId, VPlayerData);
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum GameState {
     None,
     PlayPaused,
     PlayOn,
     Goal(char),
+    /// A set piece (kick off, free kick, corner, ...) to be taken by the
+    /// specified side, along with the raw play mode.
+    SetPiece(char, String),
+    /// Offside by the specified side.
+    Offside(char),
+    /// Any other play mode, as got from the playdata source.
+    Others(String),
 }
 
 pub type Messages = HashMap<String, String>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+/// The names and scores of the teams
+pub struct TeamsInfo {
+    pub lname: String,
+    pub rname: String,
+    pub lscore: usize,
+    pub rscore: usize,
+}
+
+#[derive(Debug, Clone)]
 /// Maintain possible updates wrt a playdata update.
 /// It can contain
 /// * states like timecounter, game play state, ...
-/// * messages if any, like score, time info, game actions, ...
-/// * position of the ball
+/// * messages if any, like time info, game actions, ...
+/// * team names and scores, if got in this update
+/// * position of the ball, along with its velocity if known
 /// * positiono f the players.
 /// * stamina of the players
 pub struct PlayUpdate {
     pub timecounter: usize,
     pub state: GameState,
     pub msgs: Messages,
+    /// The team names and scores, if got in this update
+    pub teams: Option<TeamsInfo>,
     pub ball: (f32, f32),
+    /// Velocity (per record) of the ball in normal 0.0-1.0 space, if known
+    pub ballvel: Option<(f32, f32)>,
     pub lteamcoded: Vec<PlayerCodedData>,
     pub rteamcoded: Vec<PlayerCodedData>,
 }
@@ -84,7 +113,9 @@ impl PlayUpdate {
             timecounter: 0,
             state: GameState::None,
             msgs: Messages::new(),
+            teams: None,
             ball: (0.0,0.0),
+            ballvel: None,
             lteamcoded: Vec::new(),
             rteamcoded: Vec::new(),
         }
@@ -92,6 +123,20 @@ impl PlayUpdate {
 
 }
 
+/// Return the number of records due, given the time elapsed (in any unit) since
+/// the last record and the time per record (in the same unit). The time elapsed
+/// is reduced by the time wrt the records due.
+///
+/// A small tolerance, so that float rounding doesnt delay a record by a frame.
+pub fn records_due(elapsed: &mut f32, per_record: f32) -> usize {
+    let mut cnt = 0;
+    while *elapsed >= per_record*0.999 {
+        *elapsed -= per_record;
+        cnt += 1;
+    }
+    return cnt;
+}
+
 pub trait PlayData {
 
     /// Allows the playdata source to inform the main logic,
@@ -106,30 +151,63 @@ pub trait PlayData {
     fn seconds_per_record(&self) -> f32;
 
     /// Informs the data playdata source about the current
-    /// fps of the main playback gui logic.
+    /// fps of the main playback gui logic, wrt the playdata
+    /// time. ie the render fps divided by the playback speed.
     ///
     /// This allows the playdata source to respond properly
-    /// to nextframe_isrecordready calls.
+    /// to next_frame_records_ready calls.
     fn fps_changed(&mut self, fps: f32);
 
+    /// Informs the playdata source, whether the main prg is
+    /// in interpolated movements mode or not.
+    ///
+    /// This allows sources which generate the data, to adjust
+    /// the rate at which they generate records, as required.
+    fn interpolate_changed(&mut self, binterpolate: bool);
+
     /// Each time the main gui logic is about to show a new
-    /// frame, it asks the playdata source, if there is any
-    /// new playdata record available to show wrt the new frame.
+    /// frame, it asks the playdata source, as to how many
+    /// new playdata records are available to show wrt the
+    /// new frame.
     ///
-    /// NOTE: This is currently used, only if the main gui/prg
-    /// is in interpolated movements mode. In which case, the
-    /// main prg will request next_record, only if this returns
-    /// true.
-    fn next_frame_is_record_ready(&mut self) -> bool;
+    /// The main prg will request next_record, as many times
+    /// as returned by this. When the playback speed is high
+    /// relative to the render fps, this can be more than 1.
+    fn next_frame_records_ready(&mut self) -> usize;
 
     /// Request the playdata source to send the next record,
     /// available with it (immidiately).
     fn next_record(&mut self) -> PlayUpdate;
 
+    /// Get the record which will be returned by the next call to next_record,
+    /// without consuming it, if the source knows it in advance (like a log file).
+    ///
+    /// NOTE: The main logic uses this to provide the future positions, needed
+    /// by interpolators like Catmull-Rom.
+    fn peek_record(&mut self) -> Option<PlayUpdate>;
+
     /// Request the playdata source to seek either forward or
     /// backward through its list of records.
     fn seek(&mut self, seekdelta: isize);
 
+    /// Request the playdata source to seek to the specified timecounter,
+    /// such that the next record returned is the 1st one with a timecounter
+    /// equal to or beyond the specified timecounter.
+    fn seek_to_time(&mut self, timecounter: usize);
+
+    /// The timecounter wrt the last record in the source, if known.
+    fn timecounter_max(&self) -> Option<usize>;
+
+    /// Play mode changes (time, gamestate) known in advance (ie including those
+    /// beyond what has been played back till now), if any, like from a log file.
+    fn playmodes_index(&self) -> &[(usize, GameState)];
+
+    /// Whether the playdata source supports seeking through its records.
+    ///
+    /// NOTE: The main logic uses this to decide whether it can fast process
+    /// records (by calling next_record repeatedly), wrt a forward seek.
+    fn seekable(&self) -> bool;
+
     /// Playdata source informs the main program, that there is
     /// no more data available with it.
     fn bdone(&self) -> bool;
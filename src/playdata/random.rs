@@ -10,13 +10,14 @@ use crate::sdlx;
 use crate::sdlx::XSpaces;
 
 use super::PlayData;
+use super::records_due;
+use super::GameState;
 use super::PlayUpdate;
 use super::VPlayerData;
 use super::PlayerData;
 
 
 const FRAMES_NORMAL_SPR_MULT: f32 = 2.0;
-#[cfg(feature="inbetween_frames")]
 const FRAMES_INBTW_SPR_MULT: f32 = FRAMES_NORMAL_SPR_MULT*50.0;
 
 struct Team {
@@ -112,6 +113,8 @@ pub struct RandomData {
     /// frames per record
     fpr: f32,
     next: f32,
+    /// Whether the main prg is in interpolated movements mode
+    binterpolate: bool,
     ateam: Team,
     bteam: Team,
     rcnt: usize,
@@ -132,6 +135,7 @@ impl RandomData {
             base_spr,
             fpr: 0.0,
             next: 0.0,
+            binterpolate: false,
             rcnt: 0,
             s2n: XSpaces::new(srect, nrect),
             ateam: ateam,
@@ -148,16 +152,18 @@ impl RandomData {
         self.bteam.pos_fix();
     }
 
-    #[cfg(feature="inbetween_frames")]
-    fn next_external_record(&mut self, pu: &mut PlayUpdate) {
-        self.ateam.next_external_record(pu, &self.s2n, 'a', FRAMES_INBTW_SPR_MULT as usize);
-        self.bteam.next_external_record(pu, &self.s2n, 'b', FRAMES_INBTW_SPR_MULT as usize);
+    /// The multiplier wrt base_spr, based on the movements mode of the main prg
+    fn spr_mult(&self) -> f32 {
+        if self.binterpolate {
+            return FRAMES_INBTW_SPR_MULT;
+        }
+        return FRAMES_NORMAL_SPR_MULT;
     }
 
-    #[cfg(not(feature="inbetween_frames"))]
     fn next_external_record(&mut self, pu: &mut PlayUpdate) {
-        self.ateam.next_external_record(pu, &self.s2n, 'a', FRAMES_NORMAL_SPR_MULT as usize);
-        self.bteam.next_external_record(pu, &self.s2n, 'b', FRAMES_NORMAL_SPR_MULT as usize);
+        let mult = self.spr_mult() as usize;
+        self.ateam.next_external_record(pu, &self.s2n, 'a', mult);
+        self.bteam.next_external_record(pu, &self.s2n, 'b', mult);
     }
 
 }
@@ -169,30 +175,26 @@ impl PlayData for RandomData {
         self.next = 0.0;
     }
 
-    #[cfg(feature="inbetween_frames")]
-    fn seconds_per_record(&self) -> f32 {
-        self.base_spr*FRAMES_INBTW_SPR_MULT
+    fn interpolate_changed(&mut self, binterpolate: bool) {
+        self.binterpolate = binterpolate;
     }
 
-    #[cfg(not(feature="inbetween_frames"))]
     fn seconds_per_record(&self) -> f32 {
-        self.base_spr*FRAMES_NORMAL_SPR_MULT
+        self.base_spr*self.spr_mult()
     }
 
-    fn next_frame_is_record_ready(&mut self) -> bool {
-        self.next += 1.0;
-        if self.next >= self.fpr {
-            self.next = self.next - self.fpr;
-            return true;
+    fn next_frame_records_ready(&mut self) -> usize {
+        if self.fpr <= 0.0 {
+            return 1;
         }
-        return false;
+        self.next += 1.0;
+        return records_due(&mut self.next, self.fpr);
     }
 
     fn next_record(&mut self) -> PlayUpdate {
         self.rcnt += 1;
         let mut pu = PlayUpdate::new();
         // Messages
-        pu.msgs.insert("stime".to_string(), self.rcnt.to_string());
         pu.timecounter = self.rcnt;
         let gphase = (self.rcnt%3000)/1000;
         let sgphase = match gphase {
@@ -208,11 +210,32 @@ impl PlayData for RandomData {
         pu
     }
 
+    /// The records are generated randomly, so they arent known in advance.
+    fn peek_record(&mut self) -> Option<PlayUpdate> {
+        return None;
+    }
+
     fn seek(&mut self, seekdelta: isize) {
         self.rcnt = (self.rcnt as isize + seekdelta) as usize;
         return;
     }
 
+    fn seek_to_time(&mut self, timecounter: usize) {
+        self.rcnt = timecounter.saturating_sub(1);
+    }
+
+    fn timecounter_max(&self) -> Option<usize> {
+        return None;
+    }
+
+    fn playmodes_index(&self) -> &[(usize, GameState)] {
+        return &[];
+    }
+
+    fn seekable(&self) -> bool {
+        return true;
+    }
+
     fn bdone(&self) -> bool {
         return false;
     }
@@ -8,9 +8,10 @@ use loggerk::{ldebug, log_d};
 use tokensk::TStr;
 
 use crate::playdata;
-use crate::playdata::GameState;
 use crate::playdata::rcss;
+use crate::playdata::GameState;
 use crate::playdata::PlayUpdate;
+use crate::playdata::TeamsInfo;
 use crate::playdata::PlayData;
 use crate::playdata::PlayerData;
 use crate::playdata::VPlayerData;
@@ -24,6 +25,17 @@ pub struct Rcg {
     _file: File,
     lines: Vec<String>,
     iline: isize,
+    /// Index of the show records, as (line index, timecounter)
+    shows: Vec<(usize, usize)>,
+    /// Index of the play mode changes, as (timecounter, gamestate)
+    playmodes: Vec<(usize, GameState)>,
+    /// Index of the team (names and scores) records, as line index
+    teams: Vec<usize>,
+    /// The latest team info before the position seeked to, to be returned with the next record
+    teams_seeked: Option<TeamsInfo>,
+    /// The record got wrt peek_record, to be returned by the next next_record, along
+    /// with the iline and bdone after it.
+    peeked: Option<(PlayUpdate, isize, bool)>,
     pub bdone: bool,
     secondsper_record: f32,
     secondsafter_lastrecord: f32,
@@ -39,21 +51,45 @@ impl Rcg {
         let _gotr = file.read_to_string(&mut sdata).unwrap();
         let vdata = sdata.split('\n').collect::<Vec<&str>>();
         let mut vline = Vec::new();
-        for line in vdata {
+        let mut shows = Vec::new();
+        let mut playmodes = Vec::new();
+        let mut teams = Vec::new();
+        for (i, line) in vdata.iter().enumerate() {
+            if line.starts_with("(show ") {
+                let stime = line.split(' ').nth(1).unwrap_or("").parse();
+                if stime.is_err() {
+                    eprintln!("WARN:PPGND:Rcg:Skipping malformed show line:{}:{}", i+1, line);
+                    continue;
+                }
+                shows.push((vline.len(), stime.unwrap()));
+            } else if line.starts_with("(playmode ") {
+                let toks = line.trim().trim_end_matches(')').split(' ').collect::<Vec<&str>>();
+                let stime = toks[1].parse();
+                if (toks.len() < 3) || stime.is_err() {
+                    eprintln!("WARN:PPGND:Rcg:Skipping malformed playmode line:{}:{}", i+1, line);
+                    continue;
+                }
+                playmodes.push((stime.unwrap(), rcss::handle_playmode(toks[2])));
+            } else if line.starts_with("(team ") {
+                teams.push(vline.len());
+            }
             vline.push(line.to_string());
         }
-        let rrect = ((-55.0, -37.0), (55.0, 37.0));
-        let drect = ((0.0,0.0), (1.0,1.0));
         Rcg {
             _fname: fname.to_string(),
             _file: file,
             lines: vline,
             iline: -1,
+            shows: shows,
+            playmodes: playmodes,
+            teams: teams,
+            teams_seeked: None,
+            peeked: None,
             bdone: false,
             secondsper_record: rcss::SECONDS_PER_RECORD,
             secondsafter_lastrecord: 0.0,
             secondsperframe: 1.0/fps,
-            r2d: XSpaces::new(rrect, drect)
+            r2d: rcss::r2n(),
         }
     }
 
@@ -61,6 +97,35 @@ impl Rcg {
 
 impl Rcg {
 
+    /// Extract the team names and scores from a team record
+    /// (team <time> <lname> <rname> <lscore> <rscore> [<lpenscore> <rpenscore> ...])
+    fn handle_teams(toks: &Vec<String>) -> Option<TeamsInfo> {
+        if toks.len() < 6 {
+            return None;
+        }
+        return Some(TeamsInfo {
+            lname: toks[2].to_string(),
+            rname: toks[3].to_string(),
+            lscore: toks[4].parse().unwrap_or(0),
+            rscore: toks[5].parse().unwrap_or(0),
+        });
+    }
+
+    /// Note the latest team info at or before the current line, so that it is
+    /// returned with the next record. This ensures that a seek (forward or backward)
+    /// doesnt miss the score changes in the records skipped.
+    fn teams_seeked_sync(&mut self) {
+        let iteam = self.teams.partition_point(|iline| (*iline as isize) <= self.iline);
+        self.teams_seeked = None;
+        if iteam == 0 {
+            return;
+        }
+        let mut tstr = TStr::from_str(&self.lines[self.teams[iteam-1]], true);
+        tstr.peel_bracket('(').unwrap();
+        let toks = tstr.tokens_vec(' ', true, true).unwrap();
+        self.teams_seeked = Self::handle_teams(&toks);
+    }
+
     fn handle_ball(&mut self, vdata: &Vec<String>, pu: &mut PlayUpdate) {
         let fxin: f32 = vdata[1].parse().unwrap();
         let fyin: f32 = vdata[2].parse().unwrap();
@@ -70,6 +135,23 @@ impl Rcg {
             eprintln!("DBUG:{}:Ball:BeyondBoundry:{},{}:{},{}", MTAG, fxin, fyin, fx, fy);
         }
         pu.ball = (fx, fy);
+        pu.ballvel = self.handle_vel(vdata, 3);
+    }
+
+    /// Extract the velocity (if available) at the given index in the record,
+    /// converted to normal space.
+    fn handle_vel(&self, vdata: &Vec<String>, index: usize) -> Option<(f32, f32)> {
+        if vdata.len() < index+2 {
+            return None;
+        }
+        let fvx = vdata[index].parse::<f32>();
+        let fvy = vdata[index+1].parse::<f32>();
+        if fvx.is_err() || fvy.is_err() {
+            return None;
+        }
+        let vx = self.r2d.d2ox(fvx.unwrap()) - self.r2d.d2ox(0.0);
+        let vy = self.r2d.d2oy(fvy.unwrap()) - self.r2d.d2oy(0.0);
+        return Some((vx, vy));
     }
 
     fn handle_player(&mut self, vdata: &Vec<String>, pu: &mut PlayUpdate) {
@@ -102,8 +184,27 @@ impl Rcg {
             eprintln!("DBUG:{}:Player:BeyondBoundry:{},{}:{},{}", MTAG, fxin, fyin, fx, fy);
         }
         pd.push(PlayerData::Pos(fx, fy));
-        // Handle stamina
+        if let Some((vx, vy)) = self.handle_vel(vdata, 5) {
+            pd.push(PlayerData::Vel(vx, vy));
+        }
+        // Handle body and neck angles
+        if vdata.len() > 8 {
+            if let (Ok(body), Ok(neck)) = (vdata[7].parse::<f32>(), vdata[8].parse::<f32>()) {
+                pd.push(PlayerData::Body(body));
+                pd.push(PlayerData::Neck(neck));
+            }
+        }
+        // Handle view width and stamina
         for i in 5..vdata.len() {
+            if vdata[i].starts_with("(v ") {
+                let mut tstr = TStr::from_str(&vdata[i], true);
+                tstr.peel_bracket('(').unwrap();
+                let viewtoks = tstr.tokens_vec(' ', true, false).unwrap();
+                if let Some(Ok(viewwidth)) = viewtoks.get(2).map(|s| s.parse::<f32>()) {
+                    pd.push(PlayerData::ViewWidth(viewwidth));
+                }
+                continue;
+            }
             if !vdata[i].starts_with("(s ") {
                 continue;
             }
@@ -132,23 +233,29 @@ impl PlayData for Rcg {
         self.secondsperframe = 1.0/fps;
     }
 
+    fn interpolate_changed(&mut self, _binterpolate: bool) {
+    }
+
     fn seconds_per_record(&self) -> f32 {
         self.secondsper_record
     }
 
-    fn next_frame_is_record_ready(&mut self) -> bool {
+    fn next_frame_records_ready(&mut self) -> usize {
         self.secondsafter_lastrecord += self.secondsperframe;
-        if self.secondsafter_lastrecord >= self.secondsper_record {
-            self.secondsafter_lastrecord = 0.0;
-            return true;
-        }
-        return false;
+        return playdata::records_due(&mut self.secondsafter_lastrecord, self.secondsper_record);
     }
 
     fn next_record(&mut self) -> PlayUpdate {
+        if let Some((pu, iline, bdone)) = self.peeked.take() {
+            self.iline = iline;
+            self.bdone = bdone;
+            self.teams_seeked = None;
+            return pu;
+        }
         let fmtag: String = format!("{}:NextRecord", MTAG);
         let bcontinue = true;
         let mut pu = PlayUpdate::new();
+        pu.teams = self.teams_seeked.take();
         while bcontinue {
             self.iline += 1;
             if self.iline >= self.lines.len() as isize {
@@ -169,7 +276,6 @@ impl PlayData for Rcg {
             tstr.peel_bracket('(').unwrap();
             let toks = tstr.tokens_vec(' ', true, true).unwrap();
             ldebug!(&format!("DBUG:{}:Toks:Top:Full:{:?}", fmtag, toks));
-            pu.msgs.insert("stime".to_string(), toks[1].to_string());
             if toks[0].starts_with("show") {
                 pu.timecounter = toks[1].parse().unwrap();
                 for tok in toks {
@@ -188,16 +294,13 @@ impl PlayData for Rcg {
                 }
                 break;
             } else if toks[0].starts_with("playmode") {
-                if toks[2] == "goal_r" {
-                    pu.state = GameState::Goal('r');
-                } else if toks[2] == "goal_l" {
-                    pu.state = GameState::Goal('l');
-                } else if toks[2] == "play_on" {
-                    pu.state = GameState::PlayOn;
-                }
+                pu.state = rcss::handle_playmode(&toks[2]);
                 pu.msgs.insert("game".to_string(), self.lines[self.iline as usize].clone());
             } else if toks[0].starts_with("team") {
-                pu.msgs.insert("score".to_string(), self.lines[self.iline as usize].clone());
+                match Self::handle_teams(&toks) {
+                    Some(teams) => pu.teams = Some(teams),
+                    None => { pu.msgs.insert("unknown".to_string(), self.lines[self.iline as usize].clone()); },
+                }
             } else {
                 pu.msgs.insert("unknown".to_string(), self.lines[self.iline as usize].clone());
                 print!("DBUG:{}:Skipping:{:?}\n", fmtag, toks);
@@ -206,7 +309,29 @@ impl PlayData for Rcg {
         return pu;
     }
 
+    /// The record is parsed once and remembered, so that the next_record
+    /// returns it, without parsing it again.
+    fn peek_record(&mut self) -> Option<PlayUpdate> {
+        if self.bdone {
+            return None;
+        }
+        if self.peeked.is_none() {
+            let (iline, bdone, teams_seeked) = (self.iline, self.bdone, self.teams_seeked.clone());
+            let pu = self.next_record();
+            self.peeked = Some((pu, self.iline, self.bdone));
+            self.iline = iline;
+            self.bdone = bdone;
+            self.teams_seeked = teams_seeked;
+        }
+        let (pu, _iline, bdone) = self.peeked.as_ref().unwrap();
+        if *bdone {
+            return None;
+        }
+        return Some(pu.clone());
+    }
+
     fn seek(&mut self, seekdelta: isize) {
+        self.peeked = None;
         self.iline += seekdelta;
         if self.iline < 0 {
             self.iline = 0;
@@ -216,6 +341,36 @@ impl PlayData for Rcg {
         if self.lines.len() > self.iline as usize {
             self.bdone = false;
         }
+        self.teams_seeked_sync();
+    }
+
+    /// Position such that the next record read, will be the 1st show record
+    /// with timecounter equal to or beyond the specified timecounter.
+    /// Any non show records (playmode, team, ...) before it, will be read along
+    /// with it, as part of the next_record call, along with the latest team info
+    /// (names and scores) before it.
+    fn seek_to_time(&mut self, timecounter: usize) {
+        self.peeked = None;
+        let ishow = self.shows.partition_point(|show| show.1 < timecounter);
+        if ishow == 0 {
+            self.iline = -1;
+        } else {
+            self.iline = self.shows[ishow-1].0 as isize;
+        }
+        self.bdone = ishow >= self.shows.len();
+        self.teams_seeked_sync();
+    }
+
+    fn timecounter_max(&self) -> Option<usize> {
+        return self.shows.last().map(|show| show.1);
+    }
+
+    fn playmodes_index(&self) -> &[(usize, GameState)] {
+        return &self.playmodes;
+    }
+
+    fn seekable(&self) -> bool {
+        return true;
     }
 
     fn bdone(&self) -> bool {
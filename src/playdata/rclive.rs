@@ -13,7 +13,7 @@ use crate::sdlx::XSpaces;
 
 use crate::playdata;
 use super::rcss;
-use super::{PlayData, PlayUpdate, PlayerData};
+use super::{PlayData, PlayUpdate, PlayerData, GameState, TeamsInfo};
 
 
 const MTAG: &str = "GPPGND:PlayDataRCLive";
@@ -38,9 +38,10 @@ pub struct RCLive {
     bsrvraddr_updated: bool,
     /// Time wrt last message seen from server
     stime: String,
-    /// Team name and Score
-    ateam: String,
-    bteam: String,
+    /// Play mode wrt last message seen from server
+    mode: String,
+    /// Team names and scores
+    teams: TeamsInfo,
 }
 
 impl RCLive {
@@ -51,8 +52,6 @@ impl RCLive {
         let sinit = "(dispinit version 5)\r\n";
         skt.send_to(sinit.as_bytes(), addr).unwrap();
         eprintln!("DBUG:{}:New:{:?}", MTAG, skt);
-        let rrect = ((-55.0, -37.0), (55.0, 37.0));
-        let nrect = ((0.0,0.0), (1.0,1.0));
         let mut tstrx = TStrX::new();
         tstrx.flags.string_canbe_asubpart = true;
         tstrx.flags.blocktok_dlimuser_endreqd = false;
@@ -63,11 +62,11 @@ impl RCLive {
             skt: skt,
             srvraddr: addr.to_string(),
             tstrx: tstrx,
-            r2n: XSpaces::new(rrect, nrect),
+            r2n: rcss::r2n(),
             bsrvraddr_updated: false,
             stime: String::new(),
-            ateam: String::new(),
-            bteam: String::new(),
+            mode: String::new(),
+            teams: TeamsInfo { lname: String::new(), rname: String::new(), lscore: 0, rscore: 0 },
         }
     }
 
@@ -78,13 +77,19 @@ impl RCLive {
     fn handle_time(&mut self, tok: &str, pu: &mut PlayUpdate) {
         let (_,d) = tok.split_once(':').unwrap();
         pu.timecounter = d.parse().unwrap();
-        pu.msgs.insert("stime".to_string(), d.to_string());
         self.stime = d.to_string();
     }
 
+    /// The server sends the play mode with every show message,
+    /// so the game state is set only when the play mode changes.
     fn handle_mode(&mut self, tok: &str, pu: &mut PlayUpdate) {
         let (_t,d) = tok.split_once(':').unwrap();
         pu.msgs.insert("game".to_string(), format!("{}:{}", self.stime, d));
+        let mode = d.trim().trim_matches('"');
+        if mode != self.mode {
+            self.mode = mode.to_string();
+            pu.state = rcss::handle_playmode(mode);
+        }
     }
 
     fn handle_teams(&mut self, tok: &str, pu: &mut PlayUpdate) {
@@ -101,7 +106,7 @@ impl RCLive {
             let toks = tstr.tokens_vec(',', true, false).unwrap();
             let mut side = '?';
             let mut name = String::new();
-            let mut score = String::new();
+            let mut score = 0;
             for tok in toks {
                 if tok.starts_with("\"side\"") {
                     let (_,d) = tok.split_once(':').unwrap();
@@ -117,17 +122,18 @@ impl RCLive {
                 }
                 if tok.starts_with("\"score\"") {
                     let (_,d) = tok.split_once(':').unwrap();
-                    score = d.to_string();
+                    score = d.trim().parse().unwrap_or(0);
                 }
             }
-            let ts = format!("{} [{}]", name, score);
             if side == 'l' {
-                self.ateam = ts;
+                self.teams.lname = name;
+                self.teams.lscore = score;
             } else if side == 'r' {
-                self.bteam = ts;
+                self.teams.rname = name;
+                self.teams.rscore = score;
             }
         }
-        pu.msgs.insert("score".to_string(), format!("{} vs {}", self.ateam, self.bteam));
+        pu.teams = Some(self.teams.clone());
     }
 
     fn handle_ball(&mut self, tok: &str, pu: &mut PlayUpdate) {
@@ -137,6 +143,7 @@ impl RCLive {
         let toksl2 = tstr.tokens_vec(',', true, true).unwrap();
         let mut fx = 0.0;
         let mut fy = 0.0;
+        let mut vel = None;
         for tokl2 in toksl2 {
             let (k,v) = tokl2.split_once(':').unwrap();
             if k == "\"x\"" {
@@ -145,9 +152,23 @@ impl RCLive {
             if k == "\"y\"" {
                 fy = v.parse().unwrap();
             }
+            if k == "\"vx\"" {
+                vel = Some((v.parse().unwrap(), vel.unwrap_or((0.0,0.0)).1));
+            }
+            if k == "\"vy\"" {
+                vel = Some((vel.unwrap_or((0.0,0.0)).0, v.parse().unwrap()));
+            }
         }
         let (fx,fy) = self.r2n.d2o((fx,fy));
         pu.ball = (fx, fy);
+        pu.ballvel = vel.map(|vel| self.vel2n(vel));
+    }
+
+    /// Convert velocity from rcss space to normal space
+    fn vel2n(&self, vel: (f32, f32)) -> (f32, f32) {
+        let (vx, vy) = self.r2n.d2o(vel);
+        let (ox, oy) = self.r2n.d2o((0.0, 0.0));
+        return (vx - ox, vy - oy);
     }
 
     fn handle_players(&mut self, tok: &str, pu: &mut PlayUpdate) {
@@ -168,8 +189,12 @@ impl RCLive {
             let mut pnum = 0;
             let mut fx = 0.0;
             let mut fy = 0.0;
+            let mut vel = None;
             let mut side = String::new();
             let mut fstamina = 1.0f32;
+            let mut body = None;
+            let mut neck = None;
+            let mut viewwidth = None;
             let mut card = playdata::Card::None;
             let mut action = playdata::Action::None;
             // Extract the player specific datas
@@ -187,9 +212,24 @@ impl RCLive {
                 if k == "\"y\"" {
                     fy = v.parse().unwrap();
                 }
+                if k == "\"vx\"" {
+                    vel = Some((v.parse().unwrap(), vel.unwrap_or((0.0,0.0)).1));
+                }
+                if k == "\"vy\"" {
+                    vel = Some((vel.unwrap_or((0.0,0.0)).0, v.parse().unwrap()));
+                }
                 if k == "\"stamina\"" {
                     fstamina = v.parse().unwrap();
                 }
+                if k == "\"body\"" {
+                    body = Some(v.parse().unwrap());
+                }
+                if k == "\"neck\"" {
+                    neck = Some(v.parse().unwrap());
+                }
+                if k == "\"vw\"" {
+                    viewwidth = Some(v.parse().unwrap());
+                }
                 if k == "\"state\"" {
                     let state: u32 = v.parse().unwrap();
                     (action, card) = rcss::handle_state(state);
@@ -202,7 +242,20 @@ impl RCLive {
             fstamina = (fstamina/rcss::STAMINA_BASE).min(1.0);
             let mut pd = playdata::VPlayerData::new();
             pd.push(PlayerData::Pos(fx, fy));
+            if let Some(vel) = vel {
+                let (vx, vy) = self.vel2n(vel);
+                pd.push(PlayerData::Vel(vx, vy));
+            }
             pd.push(PlayerData::Stamina(fstamina));
+            if let Some(body) = body {
+                pd.push(PlayerData::Body(body));
+            }
+            if let Some(neck) = neck {
+                pd.push(PlayerData::Neck(neck));
+            }
+            if let Some(viewwidth) = viewwidth {
+                pd.push(PlayerData::ViewWidth(viewwidth));
+            }
             pd.push(PlayerData::Card(card));
             pd.push(PlayerData::Action(action));
             if side.chars().nth(1).unwrap() == 'l' {
@@ -225,8 +278,12 @@ impl PlayData for RCLive {
     fn fps_changed(&mut self, _fps: f32) {
     }
 
-    fn next_frame_is_record_ready(&mut self) -> bool {
-        return true;
+    fn interpolate_changed(&mut self, _binterpolate: bool) {
+    }
+
+    /// The server paces the records, so always try to read one.
+    fn next_frame_records_ready(&mut self) -> usize {
+        return 1;
     }
 
     ///
@@ -295,10 +352,31 @@ impl PlayData for RCLive {
         pu
     }
 
+    /// The records are got live from the server, so they arent known in advance.
+    fn peek_record(&mut self) -> Option<PlayUpdate> {
+        return None;
+    }
+
     fn seek(&mut self, _seekdelta: isize) {
         return;
     }
 
+    fn seek_to_time(&mut self, _timecounter: usize) {
+        return;
+    }
+
+    fn timecounter_max(&self) -> Option<usize> {
+        return None;
+    }
+
+    fn playmodes_index(&self) -> &[(usize, GameState)] {
+        return &[];
+    }
+
+    fn seekable(&self) -> bool {
+        return false;
+    }
+
     fn bdone(&self) -> bool {
         return false;
     }
@@ -3,12 +3,49 @@
 //! HanishKVC, 2022
 //!
 
-use super::{Card, Action};
+use super::{Card, Action, GameState};
+use crate::sdlx::{XRect, XSpaces};
 
 /// This time is infered from live record reception,
 /// Later need to check docs/src of rcss to check, if it can change
 pub const SECONDS_PER_RECORD: f32 = 0.1;
+/// The length of each half (3000 cycles) in seconds, beyond 2 halfs is extra time
+pub const HALF_TIME_SECONDS: f32 = 300.0;
 pub const STAMINA_BASE: f32 = 8000.0;
+/// The factor by which the velocity of the ball and players decay per cycle
+pub const BALL_DECAY: f32 = 0.94;
+pub const PLAYER_DECAY: f32 = 0.4;
+/// The max distance (in meters) a player can move per cycle
+pub const PLAYER_SPEED_MAX: f32 = 1.05;
+
+/// The rcss space (in meters), which is mapped to the normal 0.0-1.0 space.
+/// It includes some space around the pitch, to show the goals and players beyond.
+pub const RCSS_RECT: XRect = ((-55.0, -37.0), (55.0, 37.0));
+
+/// The rcss field dimensions (in meters)
+pub const PITCH_LENGTH: f32 = 105.0;
+pub const PITCH_WIDTH: f32 = 68.0;
+pub const PENALTY_AREA_LENGTH: f32 = 16.5;
+pub const PENALTY_AREA_WIDTH: f32 = 40.32;
+pub const GOAL_AREA_LENGTH: f32 = 5.5;
+pub const GOAL_AREA_WIDTH: f32 = 18.32;
+pub const GOAL_WIDTH: f32 = 14.02;
+pub const GOAL_DEPTH: f32 = 2.44;
+pub const PENALTY_SPOT_DIST: f32 = 11.0;
+pub const CENTER_CIRCLE_RADIUS: f32 = 9.15;
+pub const CORNER_ARC_RADIUS: f32 = 1.0;
+
+/// Conversion between the rcss space and the normal 0.0-1.0 space
+pub fn r2n() -> XSpaces {
+    return XSpaces::new(RCSS_RECT, ((0.0,0.0), (1.0,1.0)));
+}
+
+/// Play modes (prefixes) which correspond to set pieces,
+/// with the suffix (_l/_r) indicating the side taking it.
+const PLAYMODES_SETPIECE: [&str; 8] = [
+    "kick_off_", "free_kick_", "indirect_free_kick_", "corner_kick_",
+    "goal_kick_", "kick_in_", "penalty_kick_", "penalty_setup_",
+];
 
 /// Player states
 pub const STATE_KICK: u32           = 0x00002;
@@ -64,3 +101,27 @@ pub fn handle_state(state: u32) -> (Action, Card) {
     }
     return (action, card);
 }
+
+/// Map the play mode into a GameState
+pub fn handle_playmode(playmode: &str) -> GameState {
+    let playmode = playmode.trim();
+    if playmode == "play_on" {
+        return GameState::PlayOn;
+    }
+    let side = playmode.chars().last().unwrap_or('?');
+    let bsided = playmode.ends_with("_l") || playmode.ends_with("_r");
+    if bsided && playmode.starts_with("goal_") && !playmode.starts_with("goal_kick_") {
+        return GameState::Goal(side);
+    }
+    if bsided && playmode.starts_with("offside_") {
+        return GameState::Offside(side);
+    }
+    if bsided {
+        for setpiece in PLAYMODES_SETPIECE {
+            if playmode.starts_with(setpiece) && (playmode.len() == setpiece.len()+1) {
+                return GameState::SetPiece(side, playmode.to_string());
+            }
+        }
+    }
+    return GameState::Others(playmode.to_string());
+}
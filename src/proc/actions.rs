@@ -15,7 +15,7 @@ use std::fmt::Display;
 use loggerk::{ldebug, log_d};
 use sdl2::{pixels::Color, render::BlendMode};
 
-use crate::sdlx::SdlX;
+use crate::sdlx::{self, SdlX};
 use crate::{entities, playdata};
 
 
@@ -40,12 +40,20 @@ const SCORE_GOALIE_MISSED_CATCH_PENALTY_RATIO: f32 = 0.5;
 /// Scoring ratio for Otherside in a goal chain
 const SCORE_GOALCHAIN_OTHERSIDE_BEYOND_IMMIDIATE_RATIO: f32 = 0.3;
 
+/// The interval (in cycles) between the checkpoints of the players state,
+/// from which one replays the records, wrt seeking back.
+const CHECKPOINT_CYCLES: usize = 50;
+
+/// The min distance (in normal space) a successful pass should cover,
+/// for it to be treated as significant, wrt the event log.
+const LOG_PASS_NDIST_MIN: f32 = 0.25;
+
 /// Relative summary graphs wrt Best in respective Team
 pub const SUMMARY_RELATIVE_TEAM: char = 'T';
 /// Relative summary graphs wrt Best across both teams
 pub const SUMMARY_RELATIVE_ALL: char = 'A';
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 /// Maintain the scoring related to a player
 struct Score {
     /// The overall actions related score
@@ -102,6 +110,11 @@ type Pos = (f32, f32);
 struct Players {
     lplayers: Vec<(usize, Score, Pos)>,
     rplayers: Vec<(usize, Score, Pos)>,
+    /// The state of the players before any change at a time, as (time, lplayers, rplayers),
+    /// noted once every CHECKPOINT_CYCLES, so that they can be restored, when seeking back.
+    checkpoints: Vec<(usize, Vec<(usize, Score, Pos)>, Vec<(usize, Score, Pos)>)>,
+    /// Whether checkpoints should be noted or not
+    bcheckpoints: bool,
 }
 
 impl Players {
@@ -110,6 +123,8 @@ impl Players {
         let mut players = Players {
             lplayers: Vec::new(),
             rplayers: Vec::new(),
+            checkpoints: Vec::new(),
+            bcheckpoints: false,
         };
         for i in 0..lcnt {
             players.lplayers.push((i, Score::default(), (99.0,99.0)));
@@ -120,6 +135,57 @@ impl Players {
         return players;
     }
 
+    /// Get the specified player for updating.
+    fn player_mut(&mut self, side: char, playerid: usize) -> &mut (usize, Score, Pos) {
+        if side == entities::SIDE_L {
+            return &mut self.lplayers[playerid];
+        }
+        return &mut self.rplayers[playerid];
+    }
+
+    /// Note the state of the players, before any change wrt the specified time,
+    /// if CHECKPOINT_CYCLES have passed since the last checkpoint.
+    ///
+    /// The 1st checkpoint is the initial state, so it is noted wrt time 0.
+    fn checkpoint(&mut self, timecounter: usize) {
+        if !self.bcheckpoints {
+            return;
+        }
+        let cptime;
+        if let Some(last) = self.checkpoints.last() {
+            if timecounter < last.0 + CHECKPOINT_CYCLES {
+                return;
+            }
+            cptime = timecounter;
+        } else {
+            cptime = 0;
+        }
+        self.checkpoints.push((cptime, self.lplayers.clone(), self.rplayers.clone()));
+    }
+
+    /// The time of the latest checkpoint at or before the specified time, if any.
+    fn checkpoint_time(&self, timecounter: usize) -> Option<usize> {
+        let icp = self.checkpoints.partition_point(|cp| cp.0 <= timecounter);
+        if icp == 0 {
+            return None;
+        }
+        return Some(self.checkpoints[icp-1].0);
+    }
+
+    /// Restore the players to the latest checkpoint at or before the specified time,
+    /// dropping the checkpoints after it. Return the time of the checkpoint, if any.
+    fn checkpoint_restore(&mut self, timecounter: usize) -> Option<usize> {
+        let icp = self.checkpoints.partition_point(|cp| cp.0 <= timecounter);
+        if icp == 0 {
+            return None;
+        }
+        self.checkpoints.truncate(icp);
+        let cp = &self.checkpoints[icp-1];
+        self.lplayers = cp.1.clone();
+        self.rplayers = cp.2.clone();
+        return Some(cp.0);
+    }
+
     /// Help update the score of a specific player
     fn card(&mut self, side: char, playerid: usize, card: playdata::Card) {
         if playerid >= entities::XPLAYERID_START {
@@ -128,11 +194,7 @@ impl Players {
         } else {
             eprintln!("DBUG:{}:Players:Card:{}{:02}:{}", MTAG, side, playerid, card);
         }
-        if side == entities::SIDE_L {
-            self.lplayers[playerid].1.card = card;
-        } else {
-            self.rplayers[playerid].1.card = card;
-        }
+        self.player_mut(side, playerid).1.card = card;
     }
 
     /// Help update the actions related score of a specific player
@@ -143,11 +205,7 @@ impl Players {
         } else {
             eprintln!("DBUG:{}:Players:Score:{}{:02}:{}", MTAG, side, playerid, score);
         }
-        if side == entities::SIDE_L {
-            self.lplayers[playerid].1.ascore += score;
-        } else {
-            self.rplayers[playerid].1.ascore += score;
-        }
+        self.player_mut(side, playerid).1.ascore += score;
     }
 
     /// Help update the count wrt specified action of a specific player
@@ -156,26 +214,20 @@ impl Players {
             ldebug!(&format!("WARN:{}:Players:CountInc:SpecialPlayerId:{}{:02}:Ignoring...", MTAG, side, playerid));
             return;
         }
-        let player;
-        if side == entities::SIDE_L {
-            player = &mut self.lplayers[playerid];
-        } else {
-            player = &mut self.rplayers[playerid];
-        }
         let stype;
         match atype {
             AIAction::None => stype = "None",
             AIAction::Kick => {
                 stype = "Kick";
-                player.1.kicks += 1;
+                self.player_mut(side, playerid).1.kicks += 1;
             },
             AIAction::Catch => {
                 stype = "Catch";
-                player.1.catchs += 1;
+                self.player_mut(side, playerid).1.catchs += 1;
             },
             AIAction::Tackle => {
                 stype = "Tackle";
-                player.1.tackles += 1;
+                self.player_mut(side, playerid).1.tackles += 1;
             },
             AIAction::Goal => stype = "Goal",
         }
@@ -187,12 +239,7 @@ impl Players {
             ldebug!(&format!("WARN:{}:Players:DistUpdateFromPos:SpecialPlayerId:{}{:02}:Ignoring...", MTAG, side, playerid));
             return;
         }
-        let player;
-        if side == entities::SIDE_L {
-            player = &mut self.lplayers[playerid];
-        } else {
-            player = &mut self.rplayers[playerid];
-        }
+        let player = self.player_mut(side, playerid);
         let opos = player.2;
         if opos.0 == 99.0 && opos.1 == 99.0 {
             player.2 = npos;
@@ -321,6 +368,18 @@ impl ActionData {
         }
     }
 
+    pub fn side(&self) -> char {
+        return self.side;
+    }
+
+    pub fn playerid(&self) -> usize {
+        return self.playerid;
+    }
+
+    pub fn action(&self) -> &AIAction {
+        return &self.action;
+    }
+
     fn print(&self, print_aia_none: bool) {
         let mut bprint = true;
         match self.action {
@@ -342,6 +401,23 @@ impl Display for ActionData {
     }
 }
 
+#[derive(Debug, Clone)]
+/// A pass from one player to another, identified from consecutive kicks.
+/// A failed pass is one which was next kicked by the other side, in which case
+/// the receiver is the other side player who got the ball.
+pub struct PassData {
+    pub time: usize,
+    pub side: char,
+    pub from: usize,
+    pub to: usize,
+    pub success: bool,
+    /// The distance (in normal space) between the kicks
+    pub ndist: f32,
+    /// The positions (in normal space) of the kicks by the from and to players
+    pub npfrom: Pos,
+    pub npto: Pos,
+}
+
 #[derive(Debug)]
 /// Contains info about game actions and inturn performance of the players
 /// NOTE: Movement is not a action, but only a perf characteristics
@@ -351,6 +427,10 @@ pub struct ActionsInfo {
     actions: Vec<ActionData>,
     /// Contains all game actions, even same type actions which are too near in time.
     pub rawactions: Vec<ActionData>,
+    /// Contains the cards issued, as (time, side, playerid, card)
+    cards: Vec<(usize, char, usize, playdata::Card)>,
+    /// Contains the passes identified, successful or otherwise
+    passes: Vec<PassData>,
     /// Flag to indicate a seek was requested
     handle_deferedseek: bool,
 }
@@ -362,6 +442,8 @@ impl ActionsInfo {
             players: Players::new(acnt, bcnt),
             actions: Vec::new(),
             rawactions: Vec::new(),
+            cards: Vec::new(),
+            passes: Vec::new(),
             handle_deferedseek: false,
         }
     }
@@ -481,6 +563,58 @@ impl ActionsInfo {
         }
     }
 
+    /// Graphics Summary (passing network) wrt the specified side, in the given team color.
+    /// Nodes are at the average position of each player wrt its passes, sized by the
+    /// passes made and shaded by the ratio of successful passes. Edges are between
+    /// the players who passed to each other, with thickness by pass count.
+    ///
+    /// NOTE: This uses normal space positions, so is ideally drawn in world space.
+    pub fn summary_passnetwork_sdl(&self, sx: &mut SdlX, side: char, color: Color) {
+        let edges = self.pass_edges(side);
+        let avgpos = self.pass_avgpos(side);
+        sx.wc.set_blend_mode(BlendMode::Blend);
+        // Edges, combining passes in both directions between a pair of players
+        let mut pairs: Vec<(usize, usize, usize)> = Vec::new();
+        for (from, to, count, success) in &edges {
+            if !success {
+                continue;
+            }
+            let (pa, pb) = if from < to { (*from, *to) } else { (*to, *from) };
+            if let Some(pair) = pairs.iter_mut().find(|p| p.0 == pa && p.1 == pb) {
+                pair.2 += count;
+            } else {
+                pairs.push((pa, pb, *count));
+            }
+        }
+        let maxcount = pairs.iter().map(|p| p.2).max().unwrap_or(1) as f32;
+        for (pa, pb, count) in &pairs {
+            let apos = avgpos[*pa];
+            let bpos = avgpos[*pb];
+            if apos.is_none() || bpos.is_none() {
+                continue;
+            }
+            let (apos, bpos) = (apos.unwrap(), bpos.unwrap());
+            let nw = 0.002 + 0.008*(*count as f32/maxcount);
+            sx.nn_thick_line(apos.0, apos.1, bpos.0, bpos.1, nw, sdlx::color_alpha(color, 160));
+        }
+        // Nodes
+        for pi in 0..avgpos.len() {
+            let pos = avgpos[pi];
+            if pos.is_none() {
+                continue;
+            }
+            let pos = pos.unwrap();
+            let made: usize = edges.iter().filter(|e| e.0 == pi).map(|e| e.2).sum();
+            let good: usize = edges.iter().filter(|e| e.0 == pi && e.3).map(|e| e.2).sum();
+            let ratio = if made > 0 { good as f32/made as f32 } else { 0.0 };
+            let alpha = (80.0 + 160.0*ratio).round() as u8;
+            let size = 8 + (made as u32).min(16);
+            sx.wc.set_draw_color(sdlx::color_alpha(color, alpha));
+            sx.ns_fill_rect_mid(pos.0, pos.1, size, size);
+            sx.n_string(pos.0, pos.1, &format!("{}", pi), Color::WHITE);
+        }
+    }
+
     pub fn summary(&self, inc_cardscore: bool) {
         self.summary_asciiart(inc_cardscore);
         self.summary_simple(inc_cardscore);
@@ -523,11 +657,13 @@ impl ActionsInfo {
                     }
                     self.players.ascore(prevactd.side, prevactd.playerid, ppscore);
                     self.players.ascore(curactd.side, curactd.playerid, cpscore);
+                    self.pass_add(curactd, prevactd, true);
                 } else {
                     let pscore = score.0 * score.3;
                     self.players.ascore(prevactd.side, prevactd.playerid, pscore);
                     let pscore = score.0 * score.4;
                     self.players.ascore(curactd.side, curactd.playerid, pscore);
+                    self.pass_add(curactd, prevactd, false);
                 }
                 return HAReturn::Done(true);
             },
@@ -547,6 +683,30 @@ impl ActionsInfo {
         }
     }
 
+    /// Record a pass from the player of the previous kick to the player of the
+    /// current kick, provided they are different (ie not a self pass/dribble).
+    fn pass_add(&mut self, curactd: &ActionData, prevactd: &ActionData, success: bool) {
+        if prevactd.action != AIAction::Kick {
+            return;
+        }
+        if (prevactd.playerid >= entities::XPLAYERID_START) || (curactd.playerid >= entities::XPLAYERID_START) {
+            return;
+        }
+        if success && (prevactd.playerid == curactd.playerid) {
+            return;
+        }
+        self.passes.push(PassData {
+            time: curactd.time,
+            side: prevactd.side,
+            from: prevactd.playerid,
+            to: curactd.playerid,
+            success: success,
+            ndist: ((curactd.pos.0-prevactd.pos.0).powi(2) + (curactd.pos.1-prevactd.pos.1).powi(2)).sqrt(),
+            npfrom: prevactd.pos,
+            npto: curactd.pos,
+        });
+    }
+
     /// Handle a Goal Action, by trying to find the kick or tackle which might have lead to the goal.
     /// * Inturn set the player responsible for the goal.
     /// Allow for a catch action not succeeding in stopping a goal.
@@ -705,10 +865,8 @@ impl ActionsInfo {
     /// * updating action related counters
     /// * maintaing a list of raw and filtered list/vec of actions
     pub fn handle_action(&mut self, mut curactd: ActionData) {
-        if self.handle_deferedseek {
-            self.skip_after_including(curactd.time);
-            self.handle_deferedseek = false;
-        }
+        self.deferedseek_check(curactd.time);
+        self.players.checkpoint(curactd.time);
         curactd.print(false);
         let mut bupdate_actions = false;
         let mut bupdate_rawactions = true;
@@ -785,12 +943,208 @@ impl ActionsInfo {
 
 impl ActionsInfo {
 
-    pub fn handle_card(&mut self, side: char, playerid: usize, card: playdata::Card) {
+    /// Note the card issued to a player. The playdata source may repeat the card
+    /// wrt each record, while the player is carrying it, so only the 1st one is noted.
+    pub fn handle_card(&mut self, time: usize, side: char, playerid: usize, card: playdata::Card) {
+        self.deferedseek_check(time);
+        if self.cards.iter().any(|c| (c.1 == side) && (c.2 == playerid) && (c.3 == card)) {
+            return;
+        }
+        self.players.checkpoint(time);
+        self.cards.push((time, side, playerid, card.clone()));
         self.players.card(side, playerid, card);
     }
 
 }
 
+impl ActionsInfo {
+
+    /// Search through the raw actions list/vec in reverse order, for the latest
+    /// action with time in the range tmin..tmax (ie excluding tmax), which matches
+    /// * the given action type, if specified
+    /// * the given side and player, if specified
+    ///
+    /// Return the time of the matching action, if any.
+    pub fn find_rawaction_time_rev(&self, act: Option<AIAction>, player: Option<(char, usize)>, tmin: usize, tmax: usize) -> Option<usize> {
+        for i in (0..self.rawactions.len()).rev() {
+            let checkact = &self.rawactions[i];
+            if checkact.time < tmin {
+                break;
+            }
+            if checkact.time >= tmax {
+                continue;
+            }
+            if act.is_some() && (act.as_ref().unwrap() != &checkact.action) {
+                continue;
+            }
+            if player.is_some() && (player.unwrap() != (checkact.side, checkact.playerid)) {
+                continue;
+            }
+            return Some(checkact.time);
+        }
+        None
+    }
+
+    /// The pass edges wrt the specified side, as (from, to, count, success).
+    /// For failed passes, to is the other side player who got the ball.
+    pub fn pass_edges(&self, side: char) -> Vec<(usize, usize, usize, bool)> {
+        let mut edges: Vec<(usize, usize, usize, bool)> = Vec::new();
+        for pass in &self.passes {
+            if pass.side != side {
+                continue;
+            }
+            let edge = edges.iter_mut().find(|e| e.0 == pass.from && e.1 == pass.to && e.3 == pass.success);
+            if let Some(edge) = edge {
+                edge.2 += 1;
+            } else {
+                edges.push((pass.from, pass.to, 1, pass.success));
+            }
+        }
+        return edges;
+    }
+
+    /// The average position of each player of the specified side, wrt the passes
+    /// made or received by them (failed passes received belong to the other side),
+    /// None if the player wasnt involved in any pass.
+    pub fn pass_avgpos(&self, side: char) -> Vec<Option<Pos>> {
+        let nplayers = if side == entities::SIDE_L { self.players.lplayers.len() } else { self.players.rplayers.len() };
+        let mut sums = vec![((0.0, 0.0), 0); nplayers];
+        for pass in &self.passes {
+            let mut vpos = Vec::new();
+            if pass.side == side {
+                vpos.push((pass.from, pass.npfrom));
+                if pass.success {
+                    vpos.push((pass.to, pass.npto));
+                }
+            } else if !pass.success {
+                vpos.push((pass.to, pass.npto));
+            }
+            for (pi, npos) in vpos {
+                if pi >= nplayers {
+                    continue;
+                }
+                let sum = &mut sums[pi];
+                sum.0 = (sum.0.0 + npos.0, sum.0.1 + npos.1);
+                sum.1 += 1;
+            }
+        }
+        return sums.iter().map(|(npos, cnt)| {
+            if *cnt == 0 {
+                return None;
+            }
+            return Some((npos.0/(*cnt as f32), npos.1/(*cnt as f32)));
+        }).collect();
+    }
+
+    /// The details wrt the specified player, as a set of lines of text, covering
+    /// the score, action counts, distance traversed, card and the last few
+    /// significant actions.
+    pub fn player_details(&self, side: char, playerid: usize, inc_cardscore: bool, nrecent: usize) -> Vec<String> {
+        let mut vdetails = Vec::new();
+        let players = if side == entities::SIDE_L { &self.players.lplayers } else { &self.players.rplayers };
+        if playerid >= players.len() {
+            return vdetails;
+        }
+        let score = &players[playerid].1;
+        vdetails.push(format!("Score: {:.2}", score.score(inc_cardscore)));
+        vdetails.push(format!("Kicks/Tackles/Catchs: {}/{}/{}", score.kicks, score.tackles, score.catchs));
+        vdetails.push(format!("Dist(normal): {:.3}", score.dist));
+        vdetails.push(format!("Card: {}", score.card));
+        vdetails.push("Recent actions:".to_string());
+        let mut recent = Vec::new();
+        for actd in self.actions.iter().rev() {
+            if recent.len() >= nrecent {
+                break;
+            }
+            if (actd.side == side) && (actd.playerid == playerid) {
+                recent.push(format!("  {}:{}", actd.time, actd.action));
+            }
+        }
+        if recent.len() == 0 {
+            recent.push("  None".to_string());
+        }
+        vdetails.append(&mut recent);
+        return vdetails;
+    }
+
+    /// The latest (upto) maxcnt entries wrt each of goals (with the scorer), tackles,
+    /// cards and significant (long successful) passes, at or before the given time, as
+    /// (time, text), for the event log. The entries are not ordered by time across the
+    /// different types.
+    ///
+    /// The entries beyond the given time (ie if the data has been processed ahead of the
+    /// time being shown, like during seeks), are skipped.
+    pub fn log_entries(&self, maxcnt: usize, timecounter: usize) -> Vec<(usize, String)> {
+        let mut entries = Vec::new();
+        let mut cnt = 0;
+        for actd in self.rawactions.iter().rev() {
+            if cnt >= maxcnt {
+                break;
+            }
+            if actd.time > timecounter {
+                continue;
+            }
+            let text = match actd.action {
+                AIAction::Goal => {
+                    if actd.playerid >= entities::XPLAYERID_OOPS_OTHERSIDE_START {
+                        let oside = if actd.side == entities::SIDE_L { entities::SIDE_R } else { entities::SIDE_L };
+                        format!("Goal:{}:OwnGoal:{}{:02}", actd.side, oside, actd.playerid - entities::XPLAYERID_OOPS_OTHERSIDE_START)
+                    } else if actd.playerid >= entities::XPLAYERID_START {
+                        format!("Goal:{}", actd.side)
+                    } else {
+                        format!("Goal:{}:{}{:02}", actd.side, actd.side, actd.playerid)
+                    }
+                },
+                AIAction::Tackle => format!("Tackle:{}{:02}", actd.side, actd.playerid),
+                _ => continue,
+            };
+            entries.push((actd.time, text));
+            cnt += 1;
+        }
+        for (time, side, playerid, card) in self.cards.iter().rev().filter(|c| c.0 <= timecounter).take(maxcnt) {
+            entries.push((*time, format!("Card:{}:{}{:02}", card, side, playerid)));
+        }
+        let mut cnt = 0;
+        for pass in self.passes.iter().rev() {
+            if cnt >= maxcnt {
+                break;
+            }
+            if !pass.success || (pass.ndist < LOG_PASS_NDIST_MIN) || (pass.time > timecounter) {
+                continue;
+            }
+            entries.push((pass.time, format!("LongPass:{}:{:02}->{:02}", pass.side, pass.from, pass.to)));
+            cnt += 1;
+        }
+        return entries;
+    }
+
+    /// The significant actions till now, ordered by time
+    pub fn actions(&self) -> &Vec<ActionData> {
+        return &self.actions;
+    }
+
+    /// The cards issued till now, as (time, side, playerid, card)
+    pub fn cards(&self) -> &Vec<(usize, char, usize, playdata::Card)> {
+        return &self.cards;
+    }
+
+    /// Return the time of the latest card issued in the range tmin..tmax (ie excluding tmax), if any.
+    pub fn find_card_time_rev(&self, tmin: usize, tmax: usize) -> Option<usize> {
+        for i in (0..self.cards.len()).rev() {
+            let checkcard = &self.cards[i];
+            if checkcard.0 < tmin {
+                break;
+            }
+            if checkcard.0 >= tmax {
+                continue;
+            }
+            return Some(checkcard.0);
+        }
+        None
+    }
+
+}
+
 impl ActionsInfo {
 
     /// Skip all action records with a time stamp, which is
@@ -814,11 +1168,40 @@ impl ActionsInfo {
             }
             self.rawactions.pop();
         }
+        // Skip wrt passes
+        while let Some(pass) = self.passes.last() {
+            if pass.time < timecounter {
+                break;
+            }
+            self.passes.pop();
+        }
+        // Skip wrt cards
+        while let Some(card) = self.cards.last() {
+            if card.0 < timecounter {
+                break;
+            }
+            self.cards.pop();
+        }
+        // Restore wrt players scores, counts, distance, cards, ...
+        if let Some(cptime) = self.players.checkpoint_restore(timecounter) {
+            if cptime < timecounter {
+                eprintln!("WARN:{}:SkipAfterInc:Players restored to checkpoint {}, not {}", MTAG, cptime, timecounter);
+            }
+        }
         let eacnt = self.actions.len();
         let eracnt = self.rawactions.len();
         eprintln!("DBUG:{}:SkipAfterInc:A:{}->{}:RA:{}->{}", MTAG, sacnt, eacnt, sracnt, eracnt);
     }
 
+    /// If a seek was requested, skip the records at or after the time of the
+    /// 1st record seen after the seek.
+    fn deferedseek_check(&mut self, timecounter: usize) {
+        if self.handle_deferedseek {
+            self.skip_after_including(timecounter);
+            self.handle_deferedseek = false;
+        }
+    }
+
     /// There need not be records in ActionsInfo for each time step in the game,
     /// So also it wont know what is the latest/current time step active, so going
     /// back relative to current time step is not directly possible currently.
@@ -833,4 +1216,154 @@ impl ActionsInfo {
         self.handle_deferedseek = true;
     }
 
-}
\ No newline at end of file
+    /// Enable or disable the checkpoints of the players state. These are needed
+    /// only wrt seeking back, so sources which dont support seeking can avoid them.
+    pub fn set_checkpoints(&mut self, benable: bool) {
+        self.players.bcheckpoints = benable;
+        if !benable {
+            self.players.checkpoints.clear();
+        }
+    }
+
+    /// The time to seek back to, wrt the specified time, so that the players scores,
+    /// counts, ... are restored exactly, when the records from there are replayed.
+    /// ie the time of the nearest checkpoint at or before the specified time.
+    pub fn checkpoint_time(&self, timecounter: usize) -> usize {
+        return self.players.checkpoint_time(timecounter).unwrap_or(timecounter);
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NPLAYERS: usize = 3;
+
+    /// A record fed to ActionsInfo, as the entities would.
+    enum Record {
+        Action(ActionData),
+        Card(usize, char, usize, playdata::Card),
+    }
+
+    /// A synthetic game, with the players moving around, kicks passing the ball
+    /// around both the teams, along with a tackle, a catch, a goal and cards.
+    fn game(tmax: usize) -> Vec<Record> {
+        let mut recs = Vec::new();
+        for time in 0..tmax {
+            if time == 73 {
+                recs.push(Record::Action(ActionData::new(time, entities::SIDE_L, entities::XPLAYERID_UNKNOWN, (0.5, 0.0), AIAction::Goal)));
+            }
+            for (si, side) in [entities::SIDE_L, entities::SIDE_R].iter().enumerate() {
+                for pid in 0..NPLAYERS {
+                    let idx = si*NPLAYERS + pid;
+                    if (time == 40) && (idx == 4) {
+                        recs.push(Record::Card(time, *side, pid, playdata::Card::Yellow));
+                    }
+                    if (time >= 90) && (idx == 2) {
+                        recs.push(Record::Card(time, *side, pid, playdata::Card::Red));
+                    }
+                    let mut action = AIAction::None;
+                    if (time % 5 == 0) && ((time/5) % (2*NPLAYERS) == idx) {
+                        action = AIAction::Kick;
+                    } else if (time == 37) && (idx == 4) {
+                        action = AIAction::Tackle;
+                    } else if (time == 52) && (idx == 0) {
+                        action = AIAction::Catch;
+                    }
+                    let pos = (((time*7 + idx*13) % 100) as f32 / 100.0, ((time*3 + idx*29) % 100) as f32 / 100.0);
+                    recs.push(Record::Action(ActionData::new(time, *side, pid, pos, action)));
+                }
+            }
+        }
+        return recs;
+    }
+
+    /// Feed the records with time in the range tmin..tmax
+    fn feed(ai: &mut ActionsInfo, recs: &Vec<Record>, tmin: usize, tmax: usize) {
+        for rec in recs {
+            match rec {
+                Record::Action(actd) => {
+                    if (actd.time >= tmin) && (actd.time < tmax) {
+                        ai.handle_action(actd.clone());
+                    }
+                },
+                Record::Card(time, side, pid, card) => {
+                    if (*time >= tmin) && (*time < tmax) {
+                        ai.handle_card(*time, *side, *pid, card.clone());
+                    }
+                },
+            }
+        }
+    }
+
+    /// The scores, counts, distance and card of all the players, along with the
+    /// number of actions, rawactions, cards and passes.
+    fn snapshot(ai: &ActionsInfo) -> (Vec<String>, (usize, usize, usize, usize)) {
+        let mut vplayers = Vec::new();
+        for player in ai.players.lplayers.iter().chain(ai.players.rplayers.iter()) {
+            let score = &player.1;
+            vplayers.push(format!("{}:{}:{}:{}:{}:{}:{:?}", player.0, score.score(true), score.kicks, score.tackles, score.catchs, score.dist, player.2));
+        }
+        for side in [entities::SIDE_L, entities::SIDE_R] {
+            vplayers.push(format!("{}:{:?}", side, ai.pass_avgpos(side)));
+        }
+        return (vplayers, (ai.actions.len(), ai.rawactions.len(), ai.cards.len(), ai.passes.len()));
+    }
+
+    /// A ActionsInfo, with checkpoints enabled as wrt a seekable source.
+    fn actionsinfo() -> ActionsInfo {
+        let mut ai = ActionsInfo::new(NPLAYERS, NPLAYERS);
+        ai.set_checkpoints(true);
+        return ai;
+    }
+
+    /// Seek back to the checkpoint wrt the specified time, and replay the records till it.
+    fn seek_back(ai: &mut ActionsInfo, recs: &Vec<Record>, timecounter: usize) {
+        let cptime = ai.checkpoint_time(timecounter);
+        ai.seek(-1);
+        feed(ai, recs, cptime, timecounter);
+    }
+
+    #[test]
+    fn seek_back_matches_playthrough() {
+        let recs = game(120);
+        for (tseek, tend) in [(0, 30), (38, 60), (50, 75), (73, 100), (95, 120)] {
+            let mut straight = actionsinfo();
+            feed(&mut straight, &recs, 0, tend);
+            let mut seeked = actionsinfo();
+            feed(&mut seeked, &recs, 0, 120);
+            assert_eq!(seeked.players.checkpoints.len(), 1 + 119/CHECKPOINT_CYCLES);
+            seek_back(&mut seeked, &recs, tseek);
+            feed(&mut seeked, &recs, tseek, tend);
+            assert_eq!(snapshot(&seeked), snapshot(&straight), "seek to {} and play till {}", tseek, tend);
+        }
+    }
+
+    #[test]
+    fn seek_back_repeatedly() {
+        let recs = game(120);
+        let mut straight = actionsinfo();
+        feed(&mut straight, &recs, 0, 80);
+        let mut seeked = actionsinfo();
+        feed(&mut seeked, &recs, 0, 100);
+        for tseek in [90, 60, 30] {
+            seek_back(&mut seeked, &recs, tseek);
+            feed(&mut seeked, &recs, tseek, tseek+5);
+        }
+        seek_back(&mut seeked, &recs, 30);
+        feed(&mut seeked, &recs, 30, 80);
+        assert_eq!(snapshot(&seeked), snapshot(&straight));
+        assert_eq!(seeked.cards().len(), 1);
+    }
+
+    #[test]
+    fn no_checkpoints_if_not_seekable() {
+        let recs = game(120);
+        let mut ai = ActionsInfo::new(NPLAYERS, NPLAYERS);
+        feed(&mut ai, &recs, 0, 120);
+        assert_eq!(ai.players.checkpoints.len(), 0);
+        assert_eq!(ai.checkpoint_time(90), 90);
+    }
+
+}
@@ -0,0 +1,161 @@
+//!
+//! Game events and navigating through them
+//! HanishKVC, 2022
+//!
+
+use std::fmt::Display;
+
+use crate::playdata::GameState;
+use crate::proc::actions::{ActionsInfo, AIAction};
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The types of game events, one can navigate through
+pub enum EventType {
+    Goal,
+    Card,
+    SetPiece,
+    PlayMode,
+    Kick,
+    /// Any action wrt a selected player
+    PlayerAction,
+}
+
+impl Display for EventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let data = match self {
+            EventType::Goal => "Goal",
+            EventType::Card => "Card",
+            EventType::SetPiece => "SetPiece",
+            EventType::PlayMode => "PlayMode",
+            EventType::Kick => "Kick",
+            EventType::PlayerAction => "PlayerAction",
+        };
+        f.write_str(data)
+    }
+}
+
+
+#[derive(Debug)]
+/// Track the game events (beyond the actions tracked by ActionsInfo),
+/// and help find events wrt a given time range.
+pub struct GameEvents {
+    /// The play mode changes seen, as (time, gamestate)
+    playmodes: Vec<(usize, GameState)>,
+    /// Flag to indicate a seek was requested
+    handle_deferedseek: bool,
+}
+
+impl GameEvents {
+
+    pub fn new() -> GameEvents {
+        GameEvents {
+            playmodes: Vec::new(),
+            handle_deferedseek: false,
+        }
+    }
+
+    /// The play mode changes seen till now, as (time, gamestate)
+    pub fn playmodes(&self) -> &Vec<(usize, GameState)> {
+        return &self.playmodes;
+    }
+
+    /// Note the game state (if any) wrt the given time.
+    pub fn update(&mut self, timecounter: usize, state: &GameState) {
+        if self.handle_deferedseek {
+            while self.playmodes.len() > 0 && self.playmodes[self.playmodes.len()-1].0 >= timecounter {
+                self.playmodes.pop();
+            }
+            self.handle_deferedseek = false;
+        }
+        if let GameState::None = state {
+            return;
+        }
+        self.playmodes.push((timecounter, state.clone()));
+    }
+
+    /// Similar to ActionsInfo, the events at or beyond the time seeked to,
+    /// are dropped, when the next update occurs.
+    pub fn seek(&mut self) {
+        self.handle_deferedseek = true;
+    }
+
+    fn find_playmode_time_rev(&self, bsetpiece: bool, tmin: usize, tmax: usize) -> Option<usize> {
+        for i in (0..self.playmodes.len()).rev() {
+            let checkpm = &self.playmodes[i];
+            if checkpm.0 < tmin {
+                break;
+            }
+            if checkpm.0 >= tmax {
+                continue;
+            }
+            if bsetpiece {
+                if let GameState::SetPiece(_, _) = checkpm.1 {
+                    return Some(checkpm.0);
+                }
+                continue;
+            }
+            return Some(checkpm.0);
+        }
+        None
+    }
+
+    /// The log entry text wrt the given game state, if it should be logged.
+    /// Goals are skipped, as they are logged along with the scorer, from the actions.
+    fn log_playmode(state: &GameState) -> Option<String> {
+        match state {
+            GameState::None | GameState::Goal(_) => None,
+            GameState::PlayPaused => Some("PlayMode:Paused".to_string()),
+            GameState::PlayOn => Some("PlayMode:PlayOn".to_string()),
+            GameState::SetPiece(_, playmode) => Some(format!("SetPiece:{}", playmode)),
+            GameState::Offside(side) => Some(format!("Offside:{}", side)),
+            GameState::Others(playmode) => Some(format!("PlayMode:{}", playmode)),
+        }
+    }
+
+    /// The latest (upto) count entries of the event log, ordered by time, after
+    /// skipping the given number of latest entries (ie wrt scrolling back). Only the
+    /// entries at or before the given time are considered.
+    ///
+    /// The log covers the play mode changes, along with the goals, cards, tackles
+    /// and significant passes from ActionsInfo (refer its log_entries), as (time, text).
+    pub fn log_entries(&self, ainfo: &ActionsInfo, count: usize, skip: usize, timecounter: usize) -> Vec<(usize, String)> {
+        let maxcnt = count + skip;
+        let mut entries: Vec<(usize, String)> = Vec::new();
+        let mut cnt = 0;
+        for (time, state) in self.playmodes.iter().rev() {
+            if cnt >= maxcnt {
+                break;
+            }
+            if *time > timecounter {
+                continue;
+            }
+            if let Some(text) = Self::log_playmode(state) {
+                entries.push((*time, text));
+                cnt += 1;
+            }
+        }
+        entries.append(&mut ainfo.log_entries(maxcnt, timecounter));
+        // Latest first, so that the required subset can be picked, before reordering by time
+        entries.sort_by(|a, b| b.0.cmp(&a.0));
+        let mut entries: Vec<(usize, String)> = entries.into_iter().skip(skip).take(count).collect();
+        entries.reverse();
+        return entries;
+    }
+
+    /// Find the time of the latest event of the specified type, in the time range
+    /// tmin..tmax (ie excluding tmax), if any.
+    ///
+    /// player: the (side, playerid) wrt EventType::PlayerAction.
+    pub fn find_time_rev(&self, ainfo: &ActionsInfo, etype: EventType, player: (char, usize), tmin: usize, tmax: usize) -> Option<usize> {
+        match etype {
+            EventType::Goal => ainfo.find_rawaction_time_rev(Some(AIAction::Goal), None, tmin, tmax),
+            EventType::Kick => ainfo.find_rawaction_time_rev(Some(AIAction::Kick), None, tmin, tmax),
+            EventType::PlayerAction => ainfo.find_rawaction_time_rev(None, Some(player), tmin, tmax),
+            EventType::Card => ainfo.find_card_time_rev(tmin, tmax),
+            EventType::SetPiece => self.find_playmode_time_rev(true, tmin, tmax),
+            EventType::PlayMode => self.find_playmode_time_rev(false, tmin, tmax),
+        }
+    }
+
+}
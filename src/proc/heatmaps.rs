@@ -0,0 +1,282 @@
+//!
+//! Positional occupancy heatmaps
+//! HanishKVC, 2022
+//!
+
+use std::fmt::Display;
+
+use sdl2::render::BlendMode;
+
+use crate::sdlx::{self, SdlX, XRect};
+use crate::playdata::rcss;
+
+
+/// The grid size wrt the heatmaps, along the length and width of the pitch
+const HEATMAP_COLS: usize = 21;
+const HEATMAP_ROWS: usize = 14;
+
+/// The time windows (in timecounter units, ie records) one can cycle through,
+/// 0 means from the start of the game till now.
+const HEATMAP_WINDOWS: [usize; 4] = [0, 300, 1500, 3000];
+
+/// The alpha wrt the heatmap cells
+const HEATMAP_ALPHA: u8 = 120;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The entities whose occupancy is tracked and or shown as a heatmap
+pub enum HeatmapTarget {
+    None,
+    Ball,
+    /// All players of the specified side
+    Team(char),
+    /// The specified player of the specified side
+    Player(char, usize),
+}
+
+impl Display for HeatmapTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeatmapTarget::None => f.write_str("None"),
+            HeatmapTarget::Ball => f.write_str("Ball"),
+            HeatmapTarget::Team(side) => f.write_fmt(format_args!("Team{}", side)),
+            HeatmapTarget::Player(side, playerid) => f.write_fmt(format_args!("{}{:02}", side, playerid)),
+        }
+    }
+}
+
+impl HeatmapTarget {
+
+    /// Check if the given entity (Ball or Player) is covered by this target
+    fn covers(&self, entity: &HeatmapTarget) -> bool {
+        match (self, entity) {
+            (HeatmapTarget::Ball, HeatmapTarget::Ball) => true,
+            (HeatmapTarget::Team(tside), HeatmapTarget::Player(side, _)) => tside == side,
+            (HeatmapTarget::Player(_, _), HeatmapTarget::Player(_, _)) => self == entity,
+            _ => false,
+        }
+    }
+
+}
+
+
+#[derive(Debug)]
+/// Accumulate the grid cells occupied by the ball and players over time,
+/// and show the occupancy wrt a selected target and time window as a heatmap.
+pub struct Heatmaps {
+    /// The pitch area (in normal space) covered by the grid
+    nrect: XRect,
+    /// The cell occupied by each entity at each time, as (time, entity, cell index)
+    records: Vec<(usize, HeatmapTarget, usize)>,
+    /// The target whose heatmap is shown, if any
+    target: HeatmapTarget,
+    /// Index into HEATMAP_WINDOWS
+    iwindow: usize,
+    /// The occupancy count wrt each cell, for the current target and time window.
+    /// It accumulates the records in the range gstart..gend (indexes into records).
+    grid: Vec<u32>,
+    gstart: usize,
+    gend: usize,
+    /// The time wrt which the grid was last synced
+    timecounter: usize,
+    /// Flag to indicate a seek was requested
+    handle_deferedseek: bool,
+}
+
+impl Heatmaps {
+
+    pub fn new() -> Heatmaps {
+        let r2n = rcss::r2n();
+        let hl = rcss::PITCH_LENGTH/2.0;
+        let hw = rcss::PITCH_WIDTH/2.0;
+        Heatmaps {
+            nrect: (r2n.d2o((-hl, -hw)), r2n.d2o((hl, hw))),
+            records: Vec::new(),
+            target: HeatmapTarget::None,
+            iwindow: 0,
+            grid: vec![0u32; HEATMAP_COLS*HEATMAP_ROWS],
+            gstart: 0,
+            gend: 0,
+            timecounter: 0,
+            handle_deferedseek: false,
+        }
+    }
+
+    /// The grid cell index wrt the given position in normal space.
+    /// Positions beyond the pitch are mapped to the nearest cell.
+    fn cell(&self, npos: (f32, f32)) -> usize {
+        let ((x1, y1), (x2, y2)) = self.nrect;
+        let fc = ((npos.0 - x1)/(x2 - x1)).clamp(0.0, 0.9999);
+        let fr = ((npos.1 - y1)/(y2 - y1)).clamp(0.0, 0.9999);
+        let c = (fc * HEATMAP_COLS as f32) as usize;
+        let r = (fr * HEATMAP_ROWS as f32) as usize;
+        return r*HEATMAP_COLS + c;
+    }
+
+    /// Add the position of the given entity (Ball or Player) at the given time.
+    ///
+    /// If a seek was requested, the records at or beyond the given time are
+    /// dropped first (along with their counts in the grid), so that they dont
+    /// get double counted.
+    pub fn add(&mut self, time: usize, entity: HeatmapTarget, npos: (f32, f32)) {
+        if self.handle_deferedseek {
+            let keep = self.records.partition_point(|r| r.0 < time);
+            for i in keep.max(self.gstart)..self.gend {
+                self.grid_count(i, false);
+            }
+            self.records.truncate(keep);
+            self.gend = self.gend.min(keep);
+            self.gstart = self.gstart.min(keep);
+            self.handle_deferedseek = false;
+        }
+        let cell = self.cell(npos);
+        self.records.push((time, entity, cell));
+    }
+
+    pub fn seek(&mut self) {
+        self.handle_deferedseek = true;
+    }
+
+    pub fn target(&self) -> HeatmapTarget {
+        return self.target;
+    }
+
+    pub fn set_target(&mut self, target: HeatmapTarget) {
+        self.target = target;
+        self.grid_rebuild();
+    }
+
+    /// Cycle through the time windows, returning the newly set one
+    pub fn window_cycle(&mut self) -> usize {
+        self.iwindow = (self.iwindow + 1) % HEATMAP_WINDOWS.len();
+        self.grid_rebuild();
+        return HEATMAP_WINDOWS[self.iwindow];
+    }
+
+    /// Add (or remove) the specified record's count to (or from) the grid,
+    /// if it is covered by the current target.
+    fn grid_count(&mut self, irecord: usize, badd: bool) {
+        let (_time, entity, cell) = &self.records[irecord];
+        if !self.target.covers(entity) {
+            return;
+        }
+        if badd {
+            self.grid[*cell] += 1;
+        } else {
+            self.grid[*cell] -= 1;
+        }
+    }
+
+    /// Start afresh wrt the grid, for the current target and time window.
+    fn grid_rebuild(&mut self) {
+        self.grid.fill(0);
+        self.gstart = 0;
+        self.gend = 0;
+        self.update(self.timecounter);
+    }
+
+    /// Sync the grid to the given time, by adding the new records within the
+    /// time window and removing the ones which have gone out of it (either end).
+    pub fn update(&mut self, timecounter: usize) {
+        self.timecounter = timecounter;
+        let window = HEATMAP_WINDOWS[self.iwindow];
+        let tmin = if window == 0 { 0 } else { timecounter.saturating_sub(window) };
+        // Newer records
+        while (self.gend < self.records.len()) && (self.records[self.gend].0 <= timecounter) {
+            self.grid_count(self.gend, true);
+            self.gend += 1;
+        }
+        while (self.gend > 0) && (self.records[self.gend-1].0 > timecounter) {
+            self.gend -= 1;
+            if self.gend < self.gstart {
+                self.gstart = self.gend;
+            } else {
+                self.grid_count(self.gend, false);
+            }
+        }
+        // Older records
+        while (self.gstart < self.gend) && (self.records[self.gstart].0 < tmin) {
+            self.grid_count(self.gstart, false);
+            self.gstart += 1;
+        }
+        while (self.gstart > 0) && (self.records[self.gstart-1].0 >= tmin) {
+            self.gstart -= 1;
+            self.grid_count(self.gstart, true);
+        }
+    }
+
+    /// Draw the heatmap wrt the current target (if any), over the pitch.
+    /// The cells are colored relative to the most occupied cell, using a
+    /// green (least) to yellow to red (most) scale.
+    pub fn draw(&self, sx: &mut SdlX) {
+        if self.target == HeatmapTarget::None {
+            return;
+        }
+        let grid = &self.grid;
+        let max = *grid.iter().max().unwrap_or(&0);
+        if max == 0 {
+            return;
+        }
+        sx.wc.set_blend_mode(BlendMode::Blend);
+        sx.nn_fill_grid(self.nrect, HEATMAP_COLS, HEATMAP_ROWS, |i| {
+            if grid[i] == 0 {
+                return None;
+            }
+            let color = sdlx::ncolor_gyr(1.0 - (grid[i] as f32/max as f32));
+            return Some(sdlx::color_alpha(color, HEATMAP_ALPHA));
+        });
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The grid got by rescanning all the records, wrt the current target and window
+    fn grid_rescan(hm: &Heatmaps, timecounter: usize) -> Vec<u32> {
+        let mut grid = vec![0u32; HEATMAP_COLS*HEATMAP_ROWS];
+        let window = HEATMAP_WINDOWS[hm.iwindow];
+        let tmin = if window == 0 { 0 } else { timecounter.saturating_sub(window) };
+        for (time, entity, cell) in &hm.records {
+            if (*time >= tmin) && (*time <= timecounter) && hm.target.covers(entity) {
+                grid[*cell] += 1;
+            }
+        }
+        return grid;
+    }
+
+    /// Play the records from tstart till tend, with the ball and a player moving around
+    fn play(hm: &mut Heatmaps, tstart: usize, tend: usize) {
+        for time in tstart..tend {
+            let fx = (time % 97) as f32/97.0;
+            let fy = (time % 61) as f32/61.0;
+            hm.add(time, HeatmapTarget::Ball, (fx, fy));
+            hm.add(time, HeatmapTarget::Player('l', 3), (1.0-fx, fy));
+            hm.update(time);
+        }
+    }
+
+    #[test]
+    fn grid_matches_rescan() {
+        let mut hm = Heatmaps::new();
+        hm.set_target(HeatmapTarget::Ball);
+        for iwindow in 0..HEATMAP_WINDOWS.len() {
+            play(&mut hm, 0, 2000);
+            assert_eq!(hm.grid, grid_rescan(&hm, 1999));
+            // Seek back a bit and then a lot, then forward
+            for (tseek, tend) in [(1900, 1950), (100, 400), (0, 10), (10, 3500)] {
+                hm.seek();
+                play(&mut hm, tseek, tend);
+                assert_eq!(hm.grid, grid_rescan(&hm, tend-1), "window {} seek {} till {}", HEATMAP_WINDOWS[iwindow], tseek, tend);
+            }
+            hm.set_target(HeatmapTarget::Team('l'));
+            assert_eq!(hm.grid, grid_rescan(&hm, 3499));
+            hm.set_target(HeatmapTarget::Ball);
+            hm.seek();
+            hm.window_cycle();
+        }
+    }
+
+}
@@ -0,0 +1,140 @@
+//!
+//! Markers on the pitch, where the game actions happened
+//! HanishKVC, 2022
+//!
+
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::BlendMode;
+
+use crate::sdlx::SdlX;
+use crate::entities::SIDE_L;
+use crate::proc::actions::{ActionData, AIAction};
+
+
+/// The time windows (in records) one can cycle through, 0 means the whole match
+const MARKER_WINDOWS: [usize; 4] = [0, 100, 300, 1000];
+
+/// The action types one can cycle through, wrt the filter, None means all
+const MARKER_ACTIONS: [Option<AIAction>; 5] = [None, Some(AIAction::Kick), Some(AIAction::Tackle), Some(AIAction::Catch), Some(AIAction::Goal)];
+
+/// The size (half width, in pixels) of the markers
+const MARKER_SIZE: i16 = 4;
+
+/// The alpha wrt a marker which just happened, older ones fade out over the window
+const MARKER_ALPHA_MAX: f32 = 220.0;
+/// The alpha wrt markers shown for the whole match
+const MARKER_ALPHA_ALL: u8 = 160;
+
+
+#[derive(Debug)]
+/// Show markers at the positions where the significant actions happened, with
+/// * a icon per action type, ie dot for kick, cross for tackle, square for catch
+///   and triangle for goal.
+/// * the color of the team concerned.
+///
+/// The markers fade out over the time window, or else are shown for the whole
+/// match. They can be filtered by the action type and or player.
+pub struct ActionMarkers {
+    /// Whether to show the markers or not
+    pub bshow: bool,
+    /// The time window (in records) over which the markers fade out, 0 means the whole match
+    window: usize,
+    /// Index into MARKER_ACTIONS, wrt the action type filter
+    iaction: usize,
+    /// The player whose actions alone are shown, if any
+    player: Option<(char, usize)>,
+}
+
+impl ActionMarkers {
+
+    pub fn new(window: usize) -> ActionMarkers {
+        ActionMarkers {
+            bshow: false,
+            window: window,
+            iaction: 0,
+            player: None,
+        }
+    }
+
+    /// Cycle through the time windows, starting with the one larger than the current
+    pub fn window_cycle(&mut self) -> usize {
+        self.window = MARKER_WINDOWS.iter().find(|w| **w > self.window).map(|w| *w).unwrap_or(0);
+        return self.window;
+    }
+
+    /// Cycle through the action types shown, None means all
+    pub fn action_cycle(&mut self) -> Option<AIAction> {
+        self.iaction = (self.iaction + 1) % MARKER_ACTIONS.len();
+        return MARKER_ACTIONS[self.iaction].clone();
+    }
+
+    /// Set the player whose actions alone are shown, None means all players
+    pub fn set_player(&mut self, player: Option<(char, usize)>) {
+        self.player = player;
+    }
+
+    pub fn player(&self) -> Option<(char, usize)> {
+        return self.player;
+    }
+
+    /// Check if the given action passes the filters, and if so return its alpha
+    /// wrt the current time.
+    fn alpha(&self, actd: &ActionData, timecounter: usize) -> Option<u8> {
+        if actd.time > timecounter {
+            return None;
+        }
+        match MARKER_ACTIONS[self.iaction].as_ref() {
+            Some(action) => if action != actd.action() { return None; },
+            None => if *actd.action() == AIAction::None { return None; },
+        }
+        if self.player.is_some() && (self.player != Some((actd.side(), actd.playerid()))) {
+            return None;
+        }
+        if self.window == 0 {
+            return Some(MARKER_ALPHA_ALL);
+        }
+        let age = timecounter - actd.time;
+        if age >= self.window {
+            return None;
+        }
+        return Some((MARKER_ALPHA_MAX * (1.0 - age as f32/self.window as f32)).round() as u8);
+    }
+
+    /// Draw the markers wrt the given actions, in the color of the team concerned
+    pub fn draw(&self, sx: &mut SdlX, actions: &Vec<ActionData>, timecounter: usize, lcolor: Color, rcolor: Color) {
+        if !self.bshow {
+            return;
+        }
+        sx.wc.set_blend_mode(BlendMode::Blend);
+        let ms = MARKER_SIZE;
+        for actd in actions.iter() {
+            let alpha = match self.alpha(actd, timecounter) {
+                Some(alpha) => alpha,
+                None => continue,
+            };
+            let tcolor = if actd.side() == SIDE_L { lcolor } else { rcolor };
+            let color = Color::RGBA(tcolor.r, tcolor.g, tcolor.b, alpha);
+            let (sx0, sy0) = sx.n2s.d2o(actd.pos);
+            let (x, y) = (sx0.round() as i16, sy0.round() as i16);
+            match actd.action() {
+                AIAction::Kick => {
+                    sx.wc.filled_circle(x, y, ms-1, color).unwrap();
+                },
+                AIAction::Tackle => {
+                    sx.wc.thick_line(x-ms, y-ms, x+ms, y+ms, 2, color).unwrap();
+                    sx.wc.thick_line(x-ms, y+ms, x+ms, y-ms, 2, color).unwrap();
+                },
+                AIAction::Catch => {
+                    sx.wc.rectangle(x-ms, y-ms, x+ms, y+ms, color).unwrap();
+                    sx.wc.rectangle(x-ms+1, y-ms+1, x+ms-1, y+ms-1, color).unwrap();
+                },
+                AIAction::Goal => {
+                    sx.wc.filled_trigon(x, y-ms-2, x-ms-2, y+ms, x+ms+2, y+ms, color).unwrap();
+                },
+                AIAction::None => (),
+            }
+        }
+    }
+
+}
@@ -4,3 +4,9 @@
 //!
 
 pub mod actions;
+pub mod events;
+pub mod heatmaps;
+pub mod pitchcontrol;
+pub mod offside;
+pub mod shape;
+pub mod markers;
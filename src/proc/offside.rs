@@ -0,0 +1,184 @@
+//!
+//! Offside lines and checks
+//! HanishKVC, 2022
+//!
+
+use sdl2::pixels::Color;
+
+use crate::sdlx::{self, SdlX, XSpaces};
+use crate::playdata::rcss;
+use crate::entities::{SIDE_L, SIDE_R};
+
+
+/// How far back (in timecounter units) to look for the kick, wrt an offside play mode
+const OFFSIDE_KICK_LOOKBACK: usize = 50;
+
+/// Width of the offside lines, in normal space
+const OFFSIDE_NW_LINE: f32 = 0.002;
+
+/// The alpha wrt the offside lines
+const OFFSIDE_ALPHA_LINE: u8 = 200;
+
+
+/// The offside line (x in normal space) wrt the given defending side.
+///
+/// It is the second last defender or the ball, whichever is nearer to the
+/// goal line being defended, but never beyond the halfway line.
+fn offside_line(defside: char, defenders: &Vec<(f32, f32)>, ball: (f32, f32)) -> Option<f32> {
+    if defenders.len() < 2 {
+        return None;
+    }
+    let mut xs: Vec<f32> = defenders.iter().map(|p| p.0).collect();
+    let halfx = rcss::r2n().d2ox(0.0);
+    if defside == SIDE_L {
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        return Some(xs[1].min(ball.0).min(halfx));
+    }
+    xs.sort_by(|a, b| b.partial_cmp(a).unwrap());
+    return Some(xs[1].max(ball.0).max(halfx));
+}
+
+/// Check if the given position is beyond the offside line wrt the given defending side
+fn beyond_line(defside: char, line: f32, pos: (f32, f32)) -> bool {
+    if defside == SIDE_L {
+        return pos.0 < line;
+    }
+    return pos.0 > line;
+}
+
+fn otherside(side: char) -> char {
+    if side == SIDE_L { SIDE_R } else { SIDE_L }
+}
+
+
+#[derive(Debug)]
+/// Maintain the offside lines wrt both the teams, the attackers found in an offside
+/// position when their team kicks the ball, and cross check the offside play modes
+/// got from the playdata source against the same.
+pub struct Offside {
+    /// Whether to show the offside lines or not
+    pub bshow: bool,
+    /// Conversion from rcss space to normal space
+    r2n: XSpaces,
+    /// The offside lines wrt the team defending the left and right goals
+    lines: (Option<f32>, Option<f32>),
+    /// The attackers in offside position at a kick, as (time, attacking side, playerids)
+    kickchecks: Vec<(usize, char, Vec<usize>)>,
+    /// Flag to indicate a seek was requested
+    handle_deferedseek: bool,
+}
+
+impl Offside {
+
+    pub fn new() -> Offside {
+        Offside {
+            bshow: false,
+            r2n: rcss::r2n(),
+            lines: (None, None),
+            kickchecks: Vec::new(),
+            handle_deferedseek: false,
+        }
+    }
+
+    /// Update the offside lines, wrt the positions of the players and ball got last
+    pub fn update_lines(&mut self, lplayers: &Vec<(f32, f32)>, rplayers: &Vec<(f32, f32)>, ball: (f32, f32)) {
+        self.lines = (offside_line(SIDE_L, lplayers, ball), offside_line(SIDE_R, rplayers, ball));
+    }
+
+    /// Check the attackers wrt a kick by the given side, returning those (other than
+    /// the kicker) who are in an offside position.
+    ///
+    /// attackers: the positions of the kicking side players, indexed by playerid.
+    /// defenders: the positions of the other side players.
+    pub fn check_kick(&mut self, time: usize, kickside: char, kicker: usize, attackers: &Vec<(f32, f32)>, defenders: &Vec<(f32, f32)>, ball: (f32, f32)) -> Vec<usize> {
+        if self.handle_deferedseek {
+            self.kickchecks.retain(|k| k.0 < time);
+            self.handle_deferedseek = false;
+        }
+        let defside = otherside(kickside);
+        let mut offenders = Vec::new();
+        if let Some(line) = offside_line(defside, defenders, ball) {
+            for (pi, pos) in attackers.iter().enumerate() {
+                if (pi != kicker) && beyond_line(defside, line, *pos) {
+                    offenders.push(pi);
+                }
+            }
+        }
+        self.kickchecks.push((time, kickside, offenders.clone()));
+        return offenders;
+    }
+
+    /// Cross check a offside play mode (wrt the given offending side), against the
+    /// latest kick by that side. Returns a message summarising the same.
+    pub fn check_playmode(&self, time: usize, side: char) -> String {
+        for (ktime, kside, offenders) in self.kickchecks.iter().rev() {
+            if *ktime > time {
+                continue;
+            }
+            if time - *ktime > OFFSIDE_KICK_LOOKBACK {
+                break;
+            }
+            if *kside != side {
+                continue;
+            }
+            if offenders.len() > 0 {
+                return format!("Offside:{}:Agrees:{:?}@{}", side, offenders, ktime);
+            }
+            return format!("Offside:{}:Disagrees:NoneBeyondLine@{}", side, ktime);
+        }
+        return format!("Offside:{}:Disagrees:NoKickFound", side);
+    }
+
+    pub fn seek(&mut self) {
+        self.handle_deferedseek = true;
+    }
+
+    /// Draw the offside lines, as vertical lines across the pitch, in the
+    /// given color of the defending team.
+    pub fn draw(&self, sx: &mut SdlX, lcolor: Color, rcolor: Color) {
+        if !self.bshow {
+            return;
+        }
+        let ny1 = self.r2n.d2oy(-rcss::PITCH_WIDTH/2.0);
+        let ny2 = self.r2n.d2oy(rcss::PITCH_WIDTH/2.0);
+        if let Some(nx) = self.lines.0 {
+            sx.nn_thick_line(nx, ny1, nx, ny2, OFFSIDE_NW_LINE, sdlx::color_alpha(lcolor, OFFSIDE_ALPHA_LINE));
+        }
+        if let Some(nx) = self.lines.1 {
+            sx.nn_thick_line(nx, ny1, nx, ny2, OFFSIDE_NW_LINE, sdlx::color_alpha(rcolor, OFFSIDE_ALPHA_LINE));
+        }
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offside_line_second_last_defender() {
+        let r2n = rcss::r2n();
+        let n = |x: f32| r2n.d2ox(x);
+        let ldefenders = vec![(n(-50.0), 0.5), (n(-30.0), 0.4), (n(-20.0), 0.6)];
+        assert_eq!(offside_line(SIDE_L, &ldefenders, (n(-10.0), 0.5)), Some(n(-30.0)));
+        // The ball nearer to the goal line
+        assert_eq!(offside_line(SIDE_L, &ldefenders, (n(-40.0), 0.5)), Some(n(-40.0)));
+        // Never beyond the halfway line
+        let ldefenders = vec![(n(10.0), 0.5), (n(20.0), 0.4)];
+        assert_eq!(offside_line(SIDE_L, &ldefenders, (n(30.0), 0.5)), Some(n(0.0)));
+        let rdefenders = vec![(n(50.0), 0.5), (n(30.0), 0.4), (n(20.0), 0.6)];
+        assert_eq!(offside_line(SIDE_R, &rdefenders, (n(10.0), 0.5)), Some(n(30.0)));
+        assert_eq!(offside_line(SIDE_R, &rdefenders, (n(40.0), 0.5)), Some(n(40.0)));
+        assert_eq!(offside_line(SIDE_R, &vec![(n(50.0), 0.5)], (n(10.0), 0.5)), None);
+    }
+
+    #[test]
+    fn beyond_line_wrt_side() {
+        assert!(beyond_line(SIDE_L, 0.3, (0.2, 0.5)));
+        assert!(!beyond_line(SIDE_L, 0.3, (0.4, 0.5)));
+        assert!(beyond_line(SIDE_R, 0.7, (0.8, 0.5)));
+        assert!(!beyond_line(SIDE_R, 0.7, (0.6, 0.5)));
+    }
+
+}
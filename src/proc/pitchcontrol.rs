@@ -0,0 +1,199 @@
+//!
+//! Pitch control (territory) wrt the teams
+//! HanishKVC, 2022
+//!
+
+use std::fmt::Display;
+
+use sdl2::pixels::Color;
+use sdl2::render::BlendMode;
+
+use crate::sdlx::{self, SdlX, XRect, XSpaces};
+use crate::playdata::rcss;
+use crate::entities::SIDE_L;
+
+
+/// The grid size wrt pitch control, along the length and width of the pitch
+const PC_COLS: usize = 42;
+const PC_ROWS: usize = 27;
+
+/// The number of cycles a player is assumed to continue along its current
+/// velocity, before it turns and runs towards a cell, wrt time to reach.
+const PC_REACTION_CYCLES: f32 = 2.0;
+
+/// The difference in distance (meters) or time (cycles) between the teams,
+/// beyond which a cell is treated as fully controlled by the nearer team.
+const PC_MARGIN_FULL: f32 = 10.0;
+
+/// The max alpha wrt the pitch control cells
+const PC_ALPHA: f32 = 90.0;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// How pitch control is calculated and shown, if at all
+pub enum PitchControlMode {
+    None,
+    /// The team with the nearest player controls the cell
+    Voronoi,
+    /// The team whose player can reach the cell first controls it,
+    /// taking the player velocities into account, when known.
+    TimeToReach,
+}
+
+impl Display for PitchControlMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let data = match self {
+            PitchControlMode::None => "None",
+            PitchControlMode::Voronoi => "Voronoi",
+            PitchControlMode::TimeToReach => "TimeToReach",
+        };
+        f.write_str(data)
+    }
+}
+
+impl PitchControlMode {
+
+    /// The mode to switch to, when cycling through them
+    pub fn next(&self) -> PitchControlMode {
+        match self {
+            PitchControlMode::None => PitchControlMode::Voronoi,
+            PitchControlMode::Voronoi => PitchControlMode::TimeToReach,
+            PitchControlMode::TimeToReach => PitchControlMode::None,
+        }
+    }
+
+}
+
+
+/// The side, position and velocity (if known) of a player, in normal space
+pub type PCPlayer = (char, (f32, f32), Option<(f32, f32)>);
+
+#[derive(Debug)]
+/// Maintain which team controls each cell of a grid over the pitch,
+/// based on the player positions (and velocities).
+pub struct PitchControl {
+    mode: PitchControlMode,
+    /// Conversion from rcss space to normal space
+    r2n: XSpaces,
+    /// The pitch area (in normal space) covered by the grid
+    nrect: XRect,
+    /// The control wrt each cell, from -1.0 (left team) to 1.0 (right team)
+    grid: Vec<f32>,
+    /// The players wrt which the grid was computed last
+    players: Vec<PCPlayer>,
+}
+
+impl PitchControl {
+
+    pub fn new() -> PitchControl {
+        let r2n = rcss::r2n();
+        let hl = rcss::PITCH_LENGTH/2.0;
+        let hw = rcss::PITCH_WIDTH/2.0;
+        let nrect = (r2n.d2o((-hl, -hw)), r2n.d2o((hl, hw)));
+        PitchControl {
+            mode: PitchControlMode::None,
+            r2n: r2n,
+            nrect: nrect,
+            grid: vec![0.0; PC_COLS*PC_ROWS],
+            players: Vec::new(),
+        }
+    }
+
+    /// Cycle through the modes, returning the newly set one
+    pub fn mode_cycle(&mut self) -> PitchControlMode {
+        self.mode = self.mode.next();
+        self.compute();
+        return self.mode;
+    }
+
+    /// The center of the given cell, in rcss space
+    fn cell_center(&self, col: usize, row: usize) -> (f32, f32) {
+        let ((x1, y1), (x2, y2)) = self.nrect;
+        let nx = x1 + (x2-x1)*((col as f32 + 0.5)/PC_COLS as f32);
+        let ny = y1 + (y2-y1)*((row as f32 + 0.5)/PC_ROWS as f32);
+        return self.r2n.o2d((nx, ny));
+    }
+
+    /// The cost (distance or time) for the given player to reach the given point,
+    /// with both the player info and point in rcss space.
+    fn reach_cost(&self, ppos: (f32, f32), pvel: Option<(f32, f32)>, point: (f32, f32)) -> f32 {
+        match self.mode {
+            PitchControlMode::TimeToReach => {
+                // Where the player will be, after continuing along its (decaying) velocity
+                let (mut sx, mut sy) = ppos;
+                if let Some((vx, vy)) = pvel {
+                    let g = (1.0 - rcss::PLAYER_DECAY.powf(PC_REACTION_CYCLES))/(1.0 - rcss::PLAYER_DECAY);
+                    sx += vx*g;
+                    sy += vy*g;
+                }
+                let dist = ((point.0-sx).powi(2) + (point.1-sy).powi(2)).sqrt();
+                return PC_REACTION_CYCLES + dist/rcss::PLAYER_SPEED_MAX;
+            },
+            _ => {
+                return ((point.0-ppos.0).powi(2) + (point.1-ppos.1).powi(2)).sqrt();
+            },
+        }
+    }
+
+    /// Recompute the grid wrt the given players, if they have changed (moved)
+    /// since the last time.
+    pub fn update(&mut self, players: Vec<PCPlayer>) {
+        if players == self.players {
+            return;
+        }
+        self.players = players;
+        self.compute();
+    }
+
+    /// Compute the grid wrt the players got last
+    fn compute(&mut self) {
+        if self.mode == PitchControlMode::None {
+            return;
+        }
+        // Convert the players to rcss space, so that distances are in meters
+        let mut rplayers = Vec::new();
+        for (side, npos, nvel) in &self.players {
+            let rpos = self.r2n.o2d(*npos);
+            let rvel = nvel.map(|v| (self.r2n.o2dx_len(v.0), self.r2n.o2dy_len(v.1)));
+            rplayers.push((*side, rpos, rvel));
+        }
+        for row in 0..PC_ROWS {
+            for col in 0..PC_COLS {
+                let point = self.cell_center(col, row);
+                let mut lcost = f32::MAX;
+                let mut rcost = f32::MAX;
+                for (side, rpos, rvel) in &rplayers {
+                    let cost = self.reach_cost(*rpos, *rvel, point);
+                    if *side == SIDE_L {
+                        lcost = lcost.min(cost);
+                    } else {
+                        rcost = rcost.min(cost);
+                    }
+                }
+                let control;
+                if lcost == f32::MAX && rcost == f32::MAX {
+                    control = 0.0;
+                } else {
+                    control = ((lcost - rcost)/PC_MARGIN_FULL).clamp(-1.0, 1.0);
+                }
+                self.grid[row*PC_COLS + col] = control;
+            }
+        }
+    }
+
+    /// Draw the pitch control, shaded in the given team colors, with the shade
+    /// getting stronger as the control gets more certain.
+    pub fn draw(&self, sx: &mut SdlX, lcolor: Color, rcolor: Color) {
+        if self.mode == PitchControlMode::None {
+            return;
+        }
+        sx.wc.set_blend_mode(BlendMode::Blend);
+        sx.nn_fill_grid(self.nrect, PC_COLS, PC_ROWS, |i| {
+            let control = self.grid[i];
+            let alpha = (PC_ALPHA*(0.3 + 0.7*control.abs())).round() as u8;
+            let tcolor = if control < 0.0 { lcolor } else { rcolor };
+            return Some(sdlx::color_alpha(tcolor, alpha));
+        });
+    }
+
+}
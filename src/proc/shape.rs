@@ -0,0 +1,256 @@
+//!
+//! Team shape metrics
+//! HanishKVC, 2022
+//!
+
+use std::fs::File;
+use std::io::Write;
+
+use sdl2::pixels::Color;
+use sdl2::render::BlendMode;
+
+use crate::sdlx::{self, SdlX, XSpaces};
+use crate::playdata::rcss;
+use crate::entities::{SIDE_L, SIDE_R};
+
+
+/// The playerid of the goalie, who is not considered wrt the team shape
+const GOALIE_PLAYERID: usize = 0;
+
+/// The size (in pixels) of the centroid marker
+const SHAPE_CENTROID_SIZE: u32 = 8;
+
+
+/// The convex hull of the given points, in counter clockwise order
+/// (Andrew's monotone chain).
+fn convex_hull(points: &Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    let mut pts = points.clone();
+    pts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    pts.dedup();
+    if pts.len() < 3 {
+        return pts;
+    }
+    let cross = |o: (f32, f32), a: (f32, f32), b: (f32, f32)| {
+        (a.0-o.0)*(b.1-o.1) - (a.1-o.1)*(b.0-o.0)
+    };
+    let mut hull: Vec<(f32, f32)> = Vec::new();
+    for pass in 0..2 {
+        let start = hull.len();
+        for i in 0..pts.len() {
+            let p = if pass == 0 { pts[i] } else { pts[pts.len()-1-i] };
+            while hull.len() >= start+2 && cross(hull[hull.len()-2], hull[hull.len()-1], p) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // The last point is the first point of the other chain
+        hull.pop();
+    }
+    return hull;
+}
+
+/// The area of the given polygon (shoelace formula)
+fn polygon_area(points: &Vec<(f32, f32)>) -> f32 {
+    let mut area = 0.0;
+    for i in 0..points.len() {
+        let (x1, y1) = points[i];
+        let (x2, y2) = points[(i+1) % points.len()];
+        area += x1*y2 - x2*y1;
+    }
+    return (area/2.0).abs();
+}
+
+
+#[derive(Debug, Clone)]
+/// The shape of a team (excluding its goalie) at a given time.
+/// The lengths are in meters and the positions in rcss space.
+pub struct ShapeMetrics {
+    /// The convex hull of the outfield players
+    pub hull: Vec<(f32, f32)>,
+    pub centroid: (f32, f32),
+    /// Spread across the pitch (along y)
+    pub width: f32,
+    /// Spread along the pitch (along x)
+    pub depth: f32,
+    /// Area of the convex hull, smaller means more compact
+    pub compactness: f32,
+    /// Distance of the deepest outfield player from the own goal line
+    pub defline: f32,
+    /// Distance of the most advanced outfield player from the own goal line
+    pub attline: f32,
+}
+
+impl ShapeMetrics {
+
+    /// Calculate the shape metrics wrt the given side, from the given player
+    /// positions in rcss space.
+    fn new(side: char, rplayers: &Vec<(f32, f32)>) -> Option<ShapeMetrics> {
+        if rplayers.len() == 0 {
+            return None;
+        }
+        let (mut minx, mut maxx, mut miny, mut maxy) = (f32::MAX, f32::MIN, f32::MAX, f32::MIN);
+        let (mut sumx, mut sumy) = (0.0, 0.0);
+        for (x, y) in rplayers {
+            minx = minx.min(*x);
+            maxx = maxx.max(*x);
+            miny = miny.min(*y);
+            maxy = maxy.max(*y);
+            sumx += x;
+            sumy += y;
+        }
+        let cnt = rplayers.len() as f32;
+        let hgoal = rcss::PITCH_LENGTH/2.0;
+        let (defline, attline) = if side == SIDE_L {
+            (minx + hgoal, maxx + hgoal)
+        } else {
+            (hgoal - maxx, hgoal - minx)
+        };
+        let hull = convex_hull(rplayers);
+        let compactness = polygon_area(&hull);
+        Some(ShapeMetrics {
+            hull,
+            centroid: (sumx/cnt, sumy/cnt),
+            width: maxy - miny,
+            depth: maxx - minx,
+            compactness,
+            defline,
+            attline,
+        })
+    }
+
+}
+
+
+#[derive(Debug)]
+/// Maintain the shape metrics of both the teams over time, so that they
+/// can be shown, graphed and or exported.
+pub struct TeamShapes {
+    /// Whether to show the convex hull and centroid of the teams or not
+    pub bshow: bool,
+    /// Conversion from rcss space to normal space
+    r2n: XSpaces,
+    /// The shape metrics at each time, as (time, side, metrics)
+    records: Vec<(usize, char, ShapeMetrics)>,
+    /// Flag to indicate a seek was requested
+    handle_deferedseek: bool,
+}
+
+impl TeamShapes {
+
+    pub fn new() -> TeamShapes {
+        TeamShapes {
+            bshow: false,
+            r2n: rcss::r2n(),
+            records: Vec::new(),
+            handle_deferedseek: false,
+        }
+    }
+
+    /// Update the shape metrics of the given side at the given time, wrt the
+    /// given player positions (in normal space, indexed by playerid).
+    ///
+    /// If a seek was requested, the records at or beyond the given time are
+    /// dropped first.
+    pub fn update(&mut self, time: usize, side: char, nplayers: &Vec<(f32, f32)>) {
+        if self.handle_deferedseek {
+            self.records.retain(|r| r.0 < time);
+            self.handle_deferedseek = false;
+        }
+        let mut rplayers = Vec::new();
+        for (pi, npos) in nplayers.iter().enumerate() {
+            if pi == GOALIE_PLAYERID {
+                continue;
+            }
+            rplayers.push(self.r2n.o2d(*npos));
+        }
+        if let Some(metrics) = ShapeMetrics::new(side, &rplayers) {
+            self.records.push((time, side, metrics));
+        }
+    }
+
+    pub fn seek(&mut self) {
+        self.handle_deferedseek = true;
+    }
+
+    /// The latest shape metrics of the given side, at or before the given time
+    pub fn latest(&self, side: char, timecounter: usize) -> Option<&ShapeMetrics> {
+        for (time, rside, metrics) in self.records.iter().rev() {
+            if (*time <= timecounter) && (*rside == side) {
+                return Some(metrics);
+            }
+        }
+        return None;
+    }
+
+    /// Export the shape metrics of both the teams over time, as a csv file.
+    pub fn export_csv(&self, fname: &str) -> std::io::Result<usize> {
+        let mut file = File::create(fname)?;
+        writeln!(file, "time,side,centroid_x,centroid_y,width,depth,compactness,defline,attline")?;
+        for (time, side, m) in &self.records {
+            writeln!(file, "{},{},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}", time, side,
+                m.centroid.0, m.centroid.1, m.width, m.depth, m.compactness, m.defline, m.attline)?;
+        }
+        return Ok(self.records.len());
+    }
+
+    /// Draw the convex hull and centroid of both the teams, in the given team colors,
+    /// as at the given time.
+    pub fn draw(&self, sx: &mut SdlX, timecounter: usize, lcolor: Color, rcolor: Color) {
+        if !self.bshow {
+            return;
+        }
+        sx.wc.set_blend_mode(BlendMode::Blend);
+        for (side, color) in [(SIDE_L, lcolor), (SIDE_R, rcolor)] {
+            let metrics = match self.latest(side, timecounter) {
+                Some(m) => m,
+                None => continue,
+            };
+            let nhull: Vec<(f32, f32)> = metrics.hull.iter().map(|p| self.r2n.d2o(*p)).collect();
+            if nhull.len() >= 3 {
+                sx.nn_filled_polygon(&nhull, sdlx::color_alpha(color, 50));
+            }
+            for i in 0..nhull.len() {
+                let (nx1, ny1) = nhull[i];
+                let (nx2, ny2) = nhull[(i+1) % nhull.len()];
+                sx.nn_line(nx1, ny1, nx2, ny2, sdlx::color_alpha(color, 200));
+            }
+            let ncentroid = self.r2n.d2o(metrics.centroid);
+            sx.wc.set_draw_color(sdlx::color_alpha(color, 255));
+            sx.ns_fill_rect_mid(ncentroid.0, ncentroid.1, SHAPE_CENTROID_SIZE, SHAPE_CENTROID_SIZE);
+            let smetrics = format!("W{:.0} D{:.0} DL{:.0} AL{:.0}", metrics.width, metrics.depth, metrics.defline, metrics.attline);
+            sx.n_string(ncentroid.0, ncentroid.1 + 0.02, &smetrics, Color::WHITE);
+        }
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convex_hull_drops_inner_and_collinear() {
+        let points = vec![(0.0, 0.0), (4.0, 0.0), (2.0, 0.0), (4.0, 3.0), (0.0, 3.0), (2.0, 1.0), (1.0, 2.0), (4.0, 3.0)];
+        let hull = convex_hull(&points);
+        assert_eq!(hull, vec![(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)]);
+        assert_eq!(polygon_area(&hull), 12.0);
+        assert_eq!(convex_hull(&vec![(1.0, 1.0), (1.0, 1.0), (2.0, 2.0)]), vec![(1.0, 1.0), (2.0, 2.0)]);
+    }
+
+    #[test]
+    fn metrics_wrt_side() {
+        let rplayers = vec![(-30.0, -10.0), (-10.0, -10.0), (-10.0, 10.0), (-30.0, 10.0), (-20.0, 0.0)];
+        let hgoal = rcss::PITCH_LENGTH/2.0;
+        let lm = ShapeMetrics::new(SIDE_L, &rplayers).unwrap();
+        assert_eq!(lm.width, 20.0);
+        assert_eq!(lm.depth, 20.0);
+        assert_eq!(lm.compactness, 400.0);
+        assert_eq!(lm.centroid, (-20.0, 0.0));
+        assert_eq!((lm.defline, lm.attline), (hgoal - 30.0, hgoal - 10.0));
+        let rm = ShapeMetrics::new(SIDE_R, &rplayers).unwrap();
+        assert_eq!((rm.defline, rm.attline), (hgoal + 10.0, hgoal + 30.0));
+        assert!(ShapeMetrics::new(SIDE_L, &Vec::new()).is_none());
+    }
+
+}
@@ -5,9 +5,13 @@
 
 use sdl2::gfx::primitives::DrawRenderer;
 use sdl2::rect::Rect;
-use sdl2::{self, VideoSubsystem, Sdl, EventPump, ttf::Font, surface::Surface};
+use std::{env, fs};
+use std::path::{Path, PathBuf};
+
+use sdl2::{self, VideoSubsystem, Sdl, EventPump, surface::Surface};
+use sdl2::ttf::{Font, Sdl2TtfContext};
 use sdl2::render::{WindowCanvas, TextureCreator, Texture, BlendMode};
-use sdl2::video::WindowContext;
+use sdl2::video::{WindowContext, FullscreenType};
 pub use sdl2::pixels::Color;
 
 
@@ -15,10 +19,25 @@ pub const COLOR_INVISIBLE: Color = Color::RGBA(0, 0, 0, 0);
 pub const COLOR_MSG_BOX_BACKGROUND: Color = Color::RGBA(200, 200, 200, 180);
 pub const COLOR_MSG_HEAD_BACKGROUND: Color = Color::RGBA(80, 80, 80, 180);
 pub const COLOR_MSG_HEAD_TEXT: Color = Color::WHITE;
-const STRING_CHAR_PIXEL_WIDTH: f32 = 8.0;
-const STRING_CHAR_PIXEL_HEIGHT: f32 = 8.0;
+pub const COLOR_LETTERBOX: Color = Color::BLACK;
+pub const STRING_CHAR_PIXEL_WIDTH: f32 = 8.0;
+pub const STRING_CHAR_PIXEL_HEIGHT: f32 = 8.0;
 pub const TTF_FONT: &str = "/usr/share/fonts/truetype/freefont/FreeMonoBold.ttf";
 pub const TTF_FONT_SIZE: u16 = 16;
+/// The system dirs searched for a ttf font, if the configured font cant be loaded.
+/// Dirs relative to the home dir are given with a ~/ prefix.
+const TTF_FONT_DIRS: [&str; 8] = [
+    "/usr/share/fonts", "/usr/local/share/fonts", "~/.local/share/fonts", "~/.fonts",
+    "/Library/Fonts", "/System/Library/Fonts", "~/Library/Fonts", "C:\\Windows\\Fonts",
+];
+/// The font files looked for (in order) in the font dirs, else any ttf font found is used
+const TTF_FONT_NAMES: [&str; 10] = [
+    "FreeMonoBold.ttf", "DejaVuSansMono-Bold.ttf", "DejaVuSansMono.ttf", "LiberationMono-Bold.ttf",
+    "LiberationMono-Regular.ttf", "UbuntuMono-B.ttf", "NotoSansMono-Bold.ttf", "FreeMono.ttf",
+    "courbd.ttf", "cour.ttf",
+];
+/// How deep to look into the font dirs
+const TTF_FONT_DIRS_MAXDEPTH: usize = 5;
 
 
 pub static mut PRG_WIDTH: u32 = 1024;
@@ -31,6 +50,8 @@ fn update_prg_resolution(width: u32, height: u32) {
     }
 }
 
+/// The current resolution of the program window.
+/// It is updated as and when the window is resized.
 pub fn get_prg_resolution() -> (u32, u32) {
     let w;
     let h;
@@ -48,7 +69,21 @@ pub struct SdlX {
     pub wc: WindowCanvas,
     pub ep: EventPump,
     pub wctc: TextureCreator<WindowContext>,
+    /// Normal to Screen space, currently used by the drawing helpers.
+    /// It is either n2s_screen or n2s_world, refer space_screen/space_world.
     pub n2s: XSpaces,
+    /// Normal to Screen space, wrt the viewport within the window
+    n2s_screen: XSpaces,
+    /// Normal to Screen space, wrt the viewport as seen through the camera
+    n2s_world: XSpaces,
+    /// Whether the world space is currently in use
+    bworld: bool,
+    /// The aspect ratio (width/height) to maintain wrt the normal space, if any
+    naspect: Option<f32>,
+    /// Camera zoom wrt the world space
+    cam_zoom: f32,
+    /// The point in normal space, at the center of the camera view
+    cam_center: XPoint,
 }
 
 impl SdlX {
@@ -80,7 +115,9 @@ impl SdlX {
     ///
     /// Create a data space conversion mapper between
     /// * a normalised space (0.0-1.0) and
-    /// * the screen space
+    /// * the screen space, wrt the viewport within the window
+    ///
+    /// The window is resizable, refer handle_resize.
     ///
     /// If required pass hint to WM during window creation, that
     /// we dont want to participate in _NET_WM_PING mechanism
@@ -94,7 +131,7 @@ impl SdlX {
         if ignore_wmping {
             sdl2::hint::set(&String::from_utf8(sdl2::sys::SDL_HINT_VIDEO_X11_NET_WM_PING.to_vec()).unwrap(), "0");
         }
-        let win = vs.window(title, width, height).build().unwrap();
+        let win = vs.window(title, width, height).resizable().build().unwrap();
         let wc = win.into_canvas().build().unwrap();
         let wctc = wc.texture_creator();
         // Setup events
@@ -111,7 +148,122 @@ impl SdlX {
             ep: ep,
             wctc: wctc,
             n2s: XSpaces::new(drect, orect),
+            n2s_screen: XSpaces::new(drect, orect),
+            n2s_world: XSpaces::new(drect, orect),
+            bworld: false,
+            naspect: None,
+            cam_zoom: 1.0,
+            cam_center: (0.5, 0.5),
+        }
+    }
+
+    /// Drop any pending text input events
+    pub fn flush_text_input(&self) {
+        self._ctxt.event().unwrap().flush_event(sdl2::event::EventType::TextInput);
+    }
+
+    /// Set the aspect ratio (width/height) to be maintained wrt the normal space.
+    /// If None, the normal space is stretched to fill the window.
+    pub fn set_naspect(&mut self, naspect: Option<f32>) {
+        self.naspect = naspect;
+        self.viewport_update();
+    }
+
+    /// Update the viewport (ie the screen rect to which normal space is mapped) wrt
+    /// the current window resolution, letterboxing it as required, to maintain the
+    /// aspect ratio set.
+    fn viewport_update(&mut self) {
+        let (prgw, prgh) = get_prg_resolution();
+        let (fw, fh) = (prgw as f32, prgh as f32);
+        let mut orect = ((0.0,0.0), (fw, fh));
+        if let Some(naspect) = self.naspect {
+            if fw/fh > naspect {
+                let vw = fh*naspect;
+                let x1 = ((fw - vw)/2.0).round();
+                orect = ((x1, 0.0), (x1+vw, fh));
+            } else {
+                let vh = fw/naspect;
+                let y1 = ((fh - vh)/2.0).round();
+                orect = ((0.0, y1), (fw, y1+vh));
+            }
         }
+        self.n2s_screen = XSpaces::new(((0.0,0.0), (1.0,1.0)), orect);
+        self.camera_update();
+    }
+
+    /// Update the world space wrt the current camera zoom and center
+    fn camera_update(&mut self) {
+        let hview = 0.5/self.cam_zoom;
+        let (cx, cy) = self.cam_center;
+        let drect = ((cx-hview, cy-hview), (cx+hview, cy+hview));
+        self.n2s_world = XSpaces::new(drect, self.n2s_screen.orect);
+        if self.bworld {
+            self.n2s = self.n2s_world.clone();
+        } else {
+            self.n2s = self.n2s_screen.clone();
+        }
+    }
+
+    /// Set the camera zoom (1.0 or more) and the point (in normal space) at the center
+    /// of the view, wrt the world space drawing.
+    pub fn camera_set(&mut self, zoom: f32, center: XPoint) {
+        self.cam_zoom = zoom.max(1.0);
+        self.cam_center = center;
+        self.camera_update();
+    }
+
+    /// Get the camera zoom and center
+    pub fn camera(&self) -> (f32, XPoint) {
+        return (self.cam_zoom, self.cam_center);
+    }
+
+    /// Use the world space (ie as seen through the camera) wrt drawing.
+    /// Drawing is clipped to the viewport.
+    pub fn space_world(&mut self) {
+        self.bworld = true;
+        self.n2s = self.n2s_world.clone();
+        let ((x1, y1), (x2, y2)) = self.n2s_screen.orect;
+        self.wc.set_clip_rect(Some(Rect::new(x1 as i32, y1 as i32, (x2-x1) as u32, (y2-y1) as u32)));
+    }
+
+    /// Use the screen space (ie the viewport without the camera) wrt drawing.
+    /// Used for text overlays, summary graphs, ...
+    pub fn space_screen(&mut self) {
+        self.bworld = false;
+        self.n2s = self.n2s_screen.clone();
+        self.wc.set_clip_rect(None);
+    }
+
+    /// Handle a change in the window size, by updating the program resolution
+    /// and inturn the viewport.
+    pub fn handle_resize(&mut self) {
+        let (width, height) = self.wc.output_size().unwrap();
+        if (width == 0) || (height == 0) {
+            return;
+        }
+        update_prg_resolution(width, height);
+        self.viewport_update();
+        eprintln!("INFO:SdlX:Resized:{}x{}", width, height);
+    }
+
+    /// Switch between fullscreen (desktop resolution) and windowed mode.
+    pub fn toggle_fullscreen(&mut self) {
+        let win = self.wc.window_mut();
+        let ftype = if win.fullscreen_state() == FullscreenType::Off { FullscreenType::Desktop } else { FullscreenType::Off };
+        if let Err(err) = win.set_fullscreen(ftype) {
+            eprintln!("WARN:SdlX:Fullscreen:{}", err);
+        }
+        self.handle_resize();
+    }
+
+    /// Clear the window, with the viewport filled with the given color,
+    /// and any letterbox area around it filled with COLOR_LETTERBOX.
+    pub fn clear_viewport(&mut self, color: Color) {
+        self.space_screen();
+        self.wc.set_draw_color(COLOR_LETTERBOX);
+        self.wc.clear();
+        self.wc.set_draw_color(color);
+        self.nn_fill_rect(0.0, 0.0, 1.0, 1.0);
     }
 
 }
@@ -127,6 +279,68 @@ impl SdlX {
 
 }
 
+/// Collect the ttf font files in the given dir and its sub dirs, upto the given depth
+fn ttf_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth > 0 {
+                ttf_files(&path, depth-1, files);
+            }
+        } else if path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("ttf")) {
+            files.push(path);
+        }
+    }
+}
+
+/// The ttf font files in the system font dirs, with the preferred fonts first
+pub fn ttf_fonts_discover() -> Vec<String> {
+    let mut files = Vec::new();
+    let home = env::var("HOME").unwrap_or_default();
+    for dir in TTF_FONT_DIRS {
+        let dir = match dir.strip_prefix("~/") {
+            Some(rdir) => {
+                if home.is_empty() {
+                    continue;
+                }
+                Path::new(&home).join(rdir)
+            },
+            None => PathBuf::from(dir),
+        };
+        ttf_files(&dir, TTF_FONT_DIRS_MAXDEPTH, &mut files);
+    }
+    let rank = |path: &PathBuf| {
+        let fname = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        TTF_FONT_NAMES.iter().position(|name| *name == fname).unwrap_or(TTF_FONT_NAMES.len())
+    };
+    files.sort_by_key(|path| rank(path));
+    return files.iter().map(|path| path.to_string_lossy().to_string()).collect();
+}
+
+/// Load the given ttf font file, else the 1st loadable font among the system fonts
+/// (refer ttf_fonts_discover). If there is none, None is returned, inturn the
+/// builtin bitmap font is used.
+pub fn load_ttf_font<'t>(ttfx: &'t Sdl2TtfContext, ffont: &str, size: u16) -> Option<Font<'t, 'static>> {
+    if !ffont.is_empty() {
+        match ttfx.load_font(ffont, size) {
+            Ok(font) => return Some(font),
+            Err(err) => eprintln!("WARN:SdlX:LoadTTFFont:{}:{}", ffont, err),
+        }
+    }
+    for fname in ttf_fonts_discover() {
+        if let Ok(font) = ttfx.load_font(&fname, size) {
+            eprintln!("INFO:SdlX:LoadTTFFont:Using {}", fname);
+            return Some(font);
+        }
+    }
+    eprintln!("WARN:SdlX:LoadTTFFont:No ttf font found, using the builtin bitmap font");
+    return None;
+}
+
 /// Create a surface with a image of the passed text
 pub fn text_surface<'a>(font: &'a Font, text: &str, color: Color) -> Surface<'a> {
     return font.render(text).blended(color).unwrap();
@@ -137,6 +351,7 @@ pub type XPoint = (f32,f32);
 pub type XRect = (XPoint,XPoint);
 
 
+#[derive(Debug, Clone)]
 /// Allow conversion between two different 2d spaces
 pub struct XSpaces {
     /// The 1st/Data 2d space ((dx1,dy1), (dx2,dy2))
@@ -191,6 +406,16 @@ impl XSpaces {
         return (self.d2ox(d.0), self.d2oy(d.1));
     }
 
+    /// Convert a length from Data to Other space along/wrt x-axis
+    pub fn d2ox_len(&self, dl: f32) -> f32 {
+        return dl * self.d2o.0;
+    }
+
+    /// Convert a length from Data to Other space along/wrt y-axis
+    pub fn d2oy_len(&self, dl: f32) -> f32 {
+        return dl * self.d2o.1;
+    }
+
 }
 
 #[allow(dead_code)]
@@ -215,6 +440,16 @@ impl XSpaces {
         return (self.o2dx(o.0), self.o2dy(o.1));
     }
 
+    /// Convert a length from Other to Data space along/wrt x-axis
+    pub fn o2dx_len(&self, ol: f32) -> f32 {
+        return ol * self.o2d.0;
+    }
+
+    /// Convert a length from Other to Data space along/wrt y-axis
+    pub fn o2dy_len(&self, ol: f32) -> f32 {
+        return ol * self.o2d.1;
+    }
+
 }
 
 
@@ -232,12 +467,33 @@ impl SdlX {
     /// * x,y in normal space, and it represents the top-left of the rect
     /// * w,h in normal space
     pub fn nn_fill_rect(&mut self, nx: f32, ny: f32, nw: f32, nh: f32) {
-        let (sw, sh) = self.n2s.d2o((nw,nh));
-        let sw = sw.round() as u32;
-        let sh = sh.round() as u32;
+        let sw = self.n2s.d2ox_len(nw).round() as u32;
+        let sh = self.n2s.d2oy_len(nh).round() as u32;
         self.ns_fill_rect(nx, ny, sw, sh);
     }
 
+    /// Draw a grid of filled cells over the given rect (in normal space), with
+    /// the color of each cell (index is row*cols + col) got from the given fn.
+    /// Cells for which no color is returned, are left alone.
+    pub fn nn_fill_grid(&mut self, nrect: XRect, cols: usize, rows: usize, cell_color: impl Fn(usize) -> Option<Color>) {
+        let ((x1, y1), (x2, y2)) = nrect;
+        let nw = (x2 - x1)/cols as f32;
+        let nh = (y2 - y1)/rows as f32;
+        for i in 0..cols*rows {
+            let color = match cell_color(i) {
+                Some(color) => color,
+                None => continue,
+            };
+            self.wc.set_draw_color(color);
+            let nx = x1 + (i % cols) as f32*nw;
+            let ny = y1 + (i / cols) as f32*nh;
+            // Size based on the rounded cell boundries, so that there are no gaps between cells
+            let sw = self.n2s.d2ox(nx+nw).round() - self.n2s.d2ox(nx).round();
+            let sh = self.n2s.d2oy(ny+nh).round() - self.n2s.d2oy(ny).round();
+            self.ns_fill_rect(nx, ny, sw as u32, sh as u32);
+        }
+    }
+
     /// Draw a filled rect, which takes
     /// * x,y in normal space, and it represents the mid point of the rect
     /// * w,h in screen space
@@ -267,9 +523,9 @@ impl SdlX {
         let y2 = self.n2s.d2oy(ny2).round() as i16;
         let mut sw;
         if (x2-x1).abs() > (y2-y1).abs() {
-            sw = self.n2s.d2ox(nw).round() as u8;
+            sw = self.n2s.d2ox_len(nw).round() as u8;
         } else {
-            sw = self.n2s.d2oy(nw).round() as u8;
+            sw = self.n2s.d2oy_len(nw).round() as u8;
         }
         if sw < 1 {
             sw = 1;
@@ -278,7 +534,15 @@ impl SdlX {
         self.wc.thick_line(x1, y1, x2, y2, sw, color).unwrap();
     }
 
-    /// Draw a string.
+    /// Draw a filled polygon, it takes the vertices of the polygon in normal space
+    pub fn nn_filled_polygon(&mut self, npoints: &Vec<(f32, f32)>, color: Color) {
+        let vx: Vec<i16> = npoints.iter().map(|p| self.n2s.d2ox(p.0).round() as i16).collect();
+        let vy: Vec<i16> = npoints.iter().map(|p| self.n2s.d2oy(p.1).round() as i16).collect();
+        self.wc.filled_polygon(&vx, &vy, color).unwrap();
+    }
+
+    /// Draw a string, using the builtin 8x8 bitmap font of sdl gfx, so that
+    /// it doesnt depend on any ttf font being available.
     /// Takes the starting point (horiz_left-vert_mid) for drawing in normal space.
     pub fn n_string(&self, nx: f32, ny: f32, s: &str, color: Color) {
         let sx = self.n2s.d2ox(nx).round() as i16;
@@ -299,8 +563,8 @@ impl SdlX {
     /// Draw a string along with a partially translucent (gray) background box/rect.
     /// Takes the starting point for drawing in normal space.
     pub fn n_msg(&mut self, nx: f32, ny: f32, msg: &str, color: Color) {
-        let cw = self.n2s.o2dx(STRING_CHAR_PIXEL_WIDTH);
-        let ch = self.n2s.o2dy(STRING_CHAR_PIXEL_HEIGHT);
+        let cw = self.n2s.o2dx_len(STRING_CHAR_PIXEL_WIDTH);
+        let ch = self.n2s.o2dy_len(STRING_CHAR_PIXEL_HEIGHT);
         let nw = (msg.len()+2) as f32 * cw;
         let rx = nx-cw;
         let ry = ny-(ch*0.5);
@@ -313,8 +577,8 @@ impl SdlX {
     /// Draw a string along with a partially translucent (gray) background box/rect.
     /// Takes the horiz_mid-vert_mid point for drawing in normal space.
     pub fn n_msg_mid(&mut self, nx: f32, ny: f32, msg: &str, color: Color) {
-        let cw = self.n2s.o2dx(STRING_CHAR_PIXEL_WIDTH);
-        let ch = self.n2s.o2dy(STRING_CHAR_PIXEL_HEIGHT);
+        let cw = self.n2s.o2dx_len(STRING_CHAR_PIXEL_WIDTH);
+        let ch = self.n2s.o2dy_len(STRING_CHAR_PIXEL_HEIGHT);
         let nw = (msg.len()+2) as f32 * cw;
         let rx = nx - nw/2.0;
         let nx = rx+cw;
@@ -343,7 +607,7 @@ impl SdlX {
         self.wc.set_draw_color(COLOR_MSG_HEAD_BACKGROUND);
         self.nn_fill_rect(nr.0, nr.1, nr.2, nlh*(2 as f32));
         // Heading text
-        let ncw = self.n2s.o2dx(STRING_CHAR_PIXEL_WIDTH);
+        let ncw = self.n2s.o2dx_len(STRING_CHAR_PIXEL_WIDTH);
         let hlen = ss[0].len() as f32*ncw;
         let hbefore = (nr.2 - hlen)/2.0;
         //eprintln!("DBUG:SdlX:NMsgBox:[{:?}]:hlen[{}]:hb4[{}]:", nr, hlen, hbefore);
@@ -371,9 +635,28 @@ impl SdlX {
         }
     }
 
+    /// Draw a line along the given angle (in degrees, clockwise from +ve x-axis),
+    /// from sradius1 to sradius2 distance from the given point in normal space.
+    pub fn ns_radial_line(&self, nx: f32, ny: f32, sradius1: i16, sradius2: i16, angle: f32, swidth: u8, color: Color) {
+        let (sx,sy) = self.n2s.d2o((nx,ny));
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let x1 = (sx + sradius1 as f32*cos).round() as i16;
+        let y1 = (sy + sradius1 as f32*sin).round() as i16;
+        let x2 = (sx + sradius2 as f32*cos).round() as i16;
+        let y2 = (sy + sradius2 as f32*sin).round() as i16;
+        self.wc.thick_line(x1, y1, x2, y2, swidth.max(1), color).unwrap();
+    }
+
+    /// Draw a filled pie (wedge), centered at the given point in normal space.
+    /// The angles are in degrees, clockwise from +ve x-axis.
+    pub fn ns_pie(&self, nx: f32, ny: f32, sradius: i16, sstartangle: i16, sendangle: i16, color: Color) {
+        let (sx,sy) = self.n2s.d2o((nx,ny));
+        self.wc.filled_pie(sx.round() as i16, sy.round() as i16, sradius, sstartangle, sendangle, color).unwrap();
+    }
+
     #[allow(dead_code)]
     pub fn n_arc(&self, nx: f32, ny: f32, nrad: f32, nstartangle: f32, nendangle: f32, width: isize, color: Color) {
-        let radius = self.n2s.d2ox(nrad).round() as i16;
+        let radius = self.n2s.d2ox_len(nrad).round() as i16;
         let ssdeg = (nstartangle*360.0).round() as i16;
         let sedeg = (nendangle*360.0).round() as i16;
         self.ns_arc(nx, ny, radius, ssdeg, sedeg, width, color);
@@ -381,6 +664,11 @@ impl SdlX {
 
 }
 
+/// The given color with its alpha replaced
+pub fn color_alpha(color: Color, alpha: u8) -> Color {
+    Color::RGBA(color.r, color.g, color.b, alpha)
+}
+
 pub fn ncolor_gyr(ncolor: f32) -> Color {
     let mut r = 0.0;
     let mut g = 0.0;
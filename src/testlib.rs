@@ -19,7 +19,7 @@ pub fn test_ncolor() {
     }
 }
 
-pub fn test_gentity(font: &Font) {
+pub fn test_gentity(font: Option<&Font>) {
     let mut g1 = GEntity::new("test01", (0.5,0.5), (16,16), Color::WHITE, font);
     g1.set_fcolor(0.25, 1.0);
     g1.set_nxarc(1.2, 0.98, Color::RED);